@@ -0,0 +1,166 @@
+// compares solving the last layer in two looks (oll then pll, with the algorithms of
+// oll.json and pll.json) to solving it in one: every state of the last layer (62208 of
+// them) is grouped with the states which turns of the layer before and after make of it
+// (3916 cases), each case is solved with the data and searched for with a generator, the
+// search only has the generator's moves and length cap so what it finds isn't optimal and
+// it may find nothing
+
+use super::Generator;
+use crate::cube::Cube;
+use crate::cube::algorithm::{Algorithm, Move, MoveType};
+use crate::cube::last_layer::{auf, Step, DATA_FRAME};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter, Error};
+
+// the cases listed as the ones where two looks cost the most
+const COSTLIEST: usize = 20;
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct CoverageCase {
+    // a state of the case, one which the oll algorithm takes as it is
+    pub cube: Cube,
+    // how many of the states are this case
+    pub states: usize,
+    pub oll: &'static str,
+    // the pll which the oll algorithm leaves, None when the oll is missing
+    pub pll: Option<&'static str>,
+    // as written in the data (without the last turn of the layer), None when the oll or
+    // the pll is missing
+    pub two_look: Option<Algorithm>,
+    // the shortest algorithm the generator found, None when it found nothing
+    pub shortest_found: Option<Algorithm>,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Coverage {
+    // those of the generator
+    pub moves: Vec<MoveType>,
+    pub max_length: usize,
+    pub cases: Vec<CoverageCase>,
+}
+
+#[allow(dead_code)]
+impl CoverageCase {
+    // the moves two looks take more than the shortest found, negative when they take
+    // fewer, both with every turn of the layer they start with or have in between
+    pub fn cost(&self) -> Option<i32> {
+        Some(self.two_look.as_ref()?.len() as i32 - self.shortest_found.as_ref()?.len() as i32)
+    }
+}
+
+// an algorithm applied to the cube as it's written in the data, without the last turn of
+// the layer
+fn as_written(algorithm: &Algorithm) -> Algorithm {
+    let mut moves = algorithm.symmetric(&DATA_FRAME.inverse()).simplified().into_iter().collect::<Vec<_>>();
+    if moves.last().map(Move::move_type) == Some(MoveType::U) { moves.pop(); }
+    moves.into_iter().collect()
+}
+
+// the generator has to compare the whole last layer, as that of zbll does
+pub fn coverage(generator: &Generator) -> Coverage {
+    // zbll turns the layer before and after as one look would
+    let groups = Step::Zbll.grouped(Cube::last_layer_states(true, true));
+    let mut cases = groups.iter()
+        .map(|variants| {
+            let cube = variants[0].oll_case()
+                .map_or(variants[0], |oll| variants[0] * Cube::from_algorithm(&auf(oll.pre_auf)));
+            let oll = Step::Oll.named_key(&cube).unwrap_or_else(|| panic!("no oll case for\n{:?}", cube));
+            let (pll, two_look) = match cube.oll_case() {
+                Some(oll_case) => {
+                    let mut oriented = cube;
+                    oriented.apply(&oll_case.algorithm);
+                    let pll = Step::Pll.named_key(&oriented)
+                        .unwrap_or_else(|| panic!("{} doesn't orient the last layer", oll_case.name));
+                    let two_look = oriented.pll_case().map(|pll_case| as_written(&(oll_case.algorithm + pll_case.algorithm)));
+                    (Some(Step::Pll.name(&pll)), two_look)
+                }
+                None => (None, None),
+            };
+            CoverageCase {
+                cube,
+                states: variants.iter().map(Cube::packed).unique().count(),
+                oll: Step::Oll.name(&oll),
+                pll,
+                two_look,
+                shortest_found: None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let states = cases.iter().map(|case| case.cube).collect::<Vec<_>>();
+    for (case, found) in cases.iter_mut().zip(generator.solve(&states)) {
+        case.shortest_found = found.into_iter().next();
+    }
+    Coverage { moves: generator.move_types().to_vec(), max_length: generator.max_length(), cases }
+}
+
+#[allow(dead_code)]
+impl Coverage {
+    // the names of the cases of a step (oll or pll) which two looks couldn't solve
+    pub fn missing(&self, step: Step) -> Vec<&'static str> {
+        let names = self.cases.iter()
+            .filter(|case| case.two_look.is_none())
+            .filter_map(|case| match step {
+                Step::Oll if case.pll.is_none() => Some(case.oll),
+                Step::Pll => case.pll,
+                _ => None,
+            });
+        names.unique().sorted_by_key(|name| step.key(name).map(|key| step.order(&key))).collect()
+    }
+
+    // the number of moves it takes on average (over all the states), of the cases which
+    // have both algorithms so that the averages compare
+    pub fn average(&self, algorithm: impl Fn(&CoverageCase) -> Option<&Algorithm>) -> f64 {
+        let (moves, states) = self.compared()
+            .filter_map(|case| Some((algorithm(case)?.len() * case.states, case.states)))
+            .fold((0, 0), |(m, s), (moves, states)| (m + moves, s + states));
+        moves as f64 / states.max(1) as f64
+    }
+
+    // the cases which have both algorithms
+    pub fn compared(&self) -> impl Iterator<Item=&CoverageCase> {
+        self.cases.iter().filter(|case| case.cost().is_some())
+    }
+
+    // the cases which have both algorithms, where two looks cost the most first
+    pub fn costliest(&self) -> Vec<&CoverageCase> {
+        self.compared().sorted_by_key(|case| std::cmp::Reverse(case.cost())).collect()
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        for step in &[Step::Oll, Step::Pll] {
+            let missing = self.missing(*step);
+            if !missing.is_empty() {
+                let file = if *step == Step::Oll { "oll.json" } else { "pll.json" };
+                writeln!(f, "missing from {}: {}", file, missing.join(", "))?;
+            }
+        }
+        let count = |f: fn(&CoverageCase) -> bool| self.cases.iter().filter(|case| f(case)).count();
+        let moves = self.moves.iter().map(|m| format!("{:?}", m)).join("");
+        writeln!(
+            f, "{} cases ({} states), {} missing with two looks, nothing found for {} in {} up to {} moves \
+                (not counting a turn of the layer first)",
+            self.cases.len(), self.cases.iter().map(|case| case.states).sum::<usize>(),
+            count(|case| case.two_look.is_none()), count(|case| case.shortest_found.is_none()), moves, self.max_length,
+        )?;
+        writeln!(
+            f, "over the {} cases which have both, two looks take {:.2} moves on average, the shortest found {:.2}, \
+                two looks are shorter for {} cases",
+            self.compared().count(), self.average(|case| case.two_look.as_ref()),
+            self.average(|case| case.shortest_found.as_ref()), self.compared().filter(|case| case.cost() < Some(0)).count(),
+        )?;
+        writeln!(f, "two looks cost the most for:")?;
+        for case in self.costliest().into_iter().take_while(|case| case.cost() > Some(0)).take(COSTLIEST) {
+            let (two_look, found) = (case.two_look.as_ref().unwrap(), case.shortest_found.as_ref().unwrap());
+            writeln!(
+                f, "    {} then {}: \"{:?}\" ({}) against \"{:?}\" ({})",
+                case.oll, case.pll.unwrap(), two_look, two_look.len(), found, found.len(),
+            )?;
+        }
+        Ok(())
+    }
+}
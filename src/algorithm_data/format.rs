@@ -0,0 +1,384 @@
+// the data files are json, with one case per line:
+//
+// {
+//   "version": 1,
+//   "cases": [
+//     {"position":"DL","branches":[{"solved":["DB","DR"],"algorithm":"L2 U' F2"}],"algorithm":"D"},
+//     {"position":"LD","algorithm":"L' F'"}
+//   ]
+// }
+//
+// positions are written with the letters of their faces, in order, as in the moves of
+// the data: the data is turned so that the cross is on D and the last layer is U (see
+// DATA_FRAME)
+//
+// cross.json: how to solve the edge DF from its position, the first branch listing
+// exactly the solved pieces among DL, DB and DR is used, the last algorithm otherwise
+//
+// f2l.json: the same for the pair of the slot DFR from the position of its corner and
+// edge ("UFR FR"), the branches list the solved pairs among "DLB LB", "DLF LF" and
+// "DBR BR"
+//
+// oll.json and pll.json: {"name": "OLL 27", "algorithm": "R U R' U R U2 R'"} with the
+// names of last_layer.rs, as are coll.json, zbll.json, wv.json and vls.json (whose
+// cases are numbered there)
+//
+// an override file replaces some of their algorithms with someone's own, it has an
+// "oll" and a "pll" list instead of "cases" (both optional), a case is given by its
+// "name" or by its "key" (as in last_layer.rs), and its algorithm has to solve it:
+//
+// {
+//   "version": 1,
+//   "pll": [
+//     {"name": "T-perm", "algorithm": "F R U' R' U R U R2 F' R U R U' R'"},
+//     {"key": [6, 1, 2, 3, 0, 5, 4, 7], "algorithm": "L' U L' U' L' U' L' U L U L2"}
+//   ]
+// }
+
+use super::PieceKey;
+use super::verify::last_layer_problem;
+use crate::cube::algorithm::Algorithm;
+use crate::cube::last_layer::Step;
+use crate::support::Tern;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::de::value::MapAccessDeserializer;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Error};
+use std::hash::Hash;
+
+pub const VERSION: u32 = 1;
+
+// what is wrong with a data file and where
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DataError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl DataError {
+    fn new(file: &str, error: serde_json::Error) -> Self {
+        // serde_json puts the position at the end of the message
+        let message = error.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(i) => message[..i].to_string(),
+            None => message,
+        };
+        Self { file: file.to_string(), line: error.line(), column: error.column(), message }
+    }
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        // line 0 when the file couldn't be read
+        match self.line {
+            0 => write!(f, "{}: {}", self.file, self.message),
+            _ => write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message),
+        }
+    }
+}
+
+// a piece key written with letters
+struct Letters<K>(K);
+
+impl<K: PieceKey> Serialize for Letters<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.letters())
+    }
+}
+
+impl<'de, K: PieceKey> Deserialize<'de> for Letters<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        K::from_letters(&s)
+            .map(Letters)
+            .ok_or_else(|| de::Error::custom(format!("invalid position \"{}\"", s)))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields, bound = "K: PieceKey")]
+struct Branch<K> {
+    solved: Vec<Letters<K>>,
+    algorithm: Algorithm,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields, bound = "K: PieceKey")]
+struct TernCase<K> {
+    position: Letters<K>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    branches: Vec<Branch<K>>,
+    algorithm: Algorithm,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NamedCase {
+    name: String,
+    algorithm: Algorithm,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Override {
+    name: Option<String>,
+    key: Option<Vec<usize>>,
+    algorithm: Algorithm,
+}
+
+// the cases of a file as a map
+struct Cases<'a, C, K, V> {
+    entry: &'a dyn Fn(C) -> Result<(K, V), String>,
+}
+
+impl<'de, C: DeserializeOwned, K: Eq + Hash, V> Visitor<'de> for Cases<'_, C, K, V> {
+    type Value = HashMap<K, V>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "a list of cases")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut map = HashMap::new();
+        while seq.next_element_seed(Case { entry: self.entry, map: &mut map })?.is_some() {}
+        Ok(map)
+    }
+}
+
+// a case, which is turned into an entry of the map while it's read so that errors
+// point at it
+struct Case<'a, C, K, V> {
+    entry: &'a dyn Fn(C) -> Result<(K, V), String>,
+    map: &'a mut HashMap<K, V>,
+}
+
+impl<'de, C: DeserializeOwned, K: Eq + Hash, V> Visitor<'de> for Case<'_, C, K, V> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "a case")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let case = C::deserialize(MapAccessDeserializer::new(map))?;
+        let (key, value) = (self.entry)(case).map_err(de::Error::custom)?;
+        if self.map.insert(key, value).is_some() {
+            return Err(de::Error::custom("duplicate case"));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, C: DeserializeOwned, K: Eq + Hash, V> DeserializeSeed<'de> for Case<'_, C, K, V> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, C: DeserializeOwned, K: Eq + Hash, V> DeserializeSeed<'de> for Cases<'_, C, K, V> {
+    type Value = HashMap<K, V>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+// {"version": .., "cases": [..]}
+struct DataFile<'a, C, K, V>(Cases<'a, C, K, V>);
+
+impl<'de, C: DeserializeOwned, K: Eq + Hash, V> Visitor<'de> for DataFile<'_, C, K, V> {
+    type Value = HashMap<K, V>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "a data file")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut version, mut cases) = (None, None);
+        let mut seed = Some(self.0);
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "version" => {
+                    let v = map.next_value::<u32>()?;
+                    if v != VERSION {
+                        return Err(de::Error::custom(format!("unsupported version {}, expected {}", v, VERSION)));
+                    }
+                    version = Some(v);
+                }
+                "cases" => match seed.take() {
+                    Some(seed) => cases = Some(map.next_value_seed(seed)?),
+                    None => return Err(de::Error::duplicate_field("cases")),
+                },
+                _ => return Err(de::Error::unknown_field(&field, &["version", "cases"])),
+            }
+        }
+        version.ok_or_else(|| de::Error::missing_field("version"))?;
+        cases.ok_or_else(|| de::Error::missing_field("cases"))
+    }
+}
+
+// personal algorithms which replace those of oll.json and pll.json
+#[allow(dead_code)]
+#[derive(Clone, Default, Debug)]
+pub struct Overrides {
+    pub oll: HashMap<Vec<usize>, Algorithm>,
+    pub pll: HashMap<Vec<usize>, Algorithm>,
+}
+
+#[allow(dead_code)]
+impl Overrides {
+    // only oll and pll can be overridden
+    pub fn get(&self, step: Step) -> Option<&HashMap<Vec<usize>, Algorithm>> {
+        match step {
+            Step::Oll => Some(&self.oll),
+            Step::Pll => Some(&self.pll),
+            _ => None,
+        }
+    }
+
+    // the data of the step with the algorithms replaced
+    pub fn apply(&self, step: Step, mut data: HashMap<Vec<usize>, Algorithm>) -> HashMap<Vec<usize>, Algorithm> {
+        data.extend(self.get(step).into_iter().flatten().map(|(key, algorithm)| (key.clone(), algorithm.clone())));
+        data
+    }
+}
+
+// {"version": .., "oll": [..], "pll": [..]}
+struct OverrideFile<'a> {
+    oll: Cases<'a, Override, Vec<usize>, Algorithm>,
+    pll: Cases<'a, Override, Vec<usize>, Algorithm>,
+}
+
+impl<'de> Visitor<'de> for OverrideFile<'_> {
+    type Value = Overrides;
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "an override file")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        let (mut oll, mut pll) = (Some(self.oll), Some(self.pll));
+        let mut overrides = Overrides::default();
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "version" => {
+                    let v = map.next_value::<u32>()?;
+                    if v != VERSION {
+                        return Err(de::Error::custom(format!("unsupported version {}, expected {}", v, VERSION)));
+                    }
+                    version = Some(v);
+                }
+                "oll" => match oll.take() {
+                    Some(seed) => overrides.oll = map.next_value_seed(seed)?,
+                    None => return Err(de::Error::duplicate_field("oll")),
+                },
+                "pll" => match pll.take() {
+                    Some(seed) => overrides.pll = map.next_value_seed(seed)?,
+                    None => return Err(de::Error::duplicate_field("pll")),
+                },
+                _ => return Err(de::Error::unknown_field(&field, &["version", "oll", "pll"])),
+            }
+        }
+        version.ok_or_else(|| de::Error::missing_field("version"))?;
+        Ok(overrides)
+    }
+}
+
+// the case of an override, which has to be solved by its algorithm
+fn override_entry(step: Step, case: Override) -> Result<(Vec<usize>, Algorithm), String> {
+    let key = match (case.name, case.key) {
+        (Some(name), None) => step.key(&name).ok_or_else(|| format!("unknown case \"{}\"", name))?,
+        (None, Some(key)) if step.keys().contains(&key) => key,
+        (None, Some(key)) => return Err(format!("unknown case {:?}", key)),
+        _ => return Err("expected either a name or a key".to_string()),
+    };
+    match last_layer_problem(step, &key, &case.algorithm) {
+        Some(problem) => Err(format!("\"{:?}\" doesn't solve {}, {}", case.algorithm, step.name(&key), problem)),
+        None => Ok((key, case.algorithm)),
+    }
+}
+
+pub(crate) fn load_overrides(file: &str, text: &str) -> Result<Overrides, DataError> {
+    let oll = |case| override_entry(Step::Oll, case);
+    let pll = |case| override_entry(Step::Pll, case);
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    deserializer.deserialize_map(OverrideFile { oll: Cases { entry: &oll }, pll: Cases { entry: &pll } })
+        .and_then(|overrides| deserializer.end().map(|_| overrides))
+        .map_err(|e| DataError::new(file, e))
+}
+
+fn load<C: DeserializeOwned, K: Eq + Hash, V>(
+    file: &str,
+    text: &str,
+    entry: impl Fn(C) -> Result<(K, V), String>,
+) -> Result<HashMap<K, V>, DataError> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    deserializer.deserialize_map(DataFile(Cases { entry: &entry }))
+        .and_then(|map| deserializer.end().map(|_| map))
+        .map_err(|e| DataError::new(file, e))
+}
+
+pub(crate) fn load_tern<K: PieceKey + Eq + Hash>(file: &str, text: &str)
+    -> Result<HashMap<K, Tern<Vec<K>, Algorithm>>, DataError>
+{
+    load(file, text, |case: TernCase<K>| {
+        let branches = case.branches.into_iter()
+            .map(|b| (b.solved.into_iter().map(|l| l.0).collect(), b.algorithm))
+            .collect();
+        Ok((case.position.0, Tern::new(branches, case.algorithm)))
+    })
+}
+
+pub(crate) fn load_last_layer(step: Step, file: &str, text: &str)
+    -> Result<HashMap<Vec<usize>, Algorithm>, DataError>
+{
+    load(file, text, |case: NamedCase| {
+        let key = step.key(&case.name).ok_or_else(|| format!("unknown case \"{}\"", case.name))?;
+        Ok((key, case.algorithm))
+    })
+}
+
+fn write_file<C: Serialize>(cases: impl Iterator<Item=C>) -> String {
+    let cases = cases
+        .map(|c| format!("    {}", serde_json::to_string(&c).unwrap()))
+        .collect::<Vec<_>>();
+    format!("{{\n  \"version\": {},\n  \"cases\": [\n{}\n  ]\n}}\n", VERSION, cases.join(",\n"))
+}
+
+// the data of cross.json or f2l.json, ordered by position
+pub(crate) fn write_tern<K: PieceKey + Copy + Eq>(data: &HashMap<K, Tern<Vec<K>, Algorithm>>) -> String {
+    let mut cases = data.iter()
+        .map(|(key, tern)| {
+            let mut branches = vec![];
+            let mut tern = tern;
+            while let Tern::Con(solved, algorithm, next) = tern {
+                let solved = solved.iter().map(|k| Letters(*k)).collect();
+                branches.push(Branch { solved, algorithm: algorithm.clone() });
+                tern = next;
+            }
+            let algorithm = match tern {
+                Tern::End(algorithm) => algorithm.clone(),
+                Tern::Con(..) => unreachable!(),
+            };
+            TernCase { position: Letters(*key), branches, algorithm }
+        })
+        .collect::<Vec<_>>();
+    cases.sort_by_key(|c| c.position.0.letters());
+    write_file(cases.into_iter())
+}
+
+// the data of oll.json or pll.json, in the usual order
+pub(crate) fn write_last_layer(step: Step, data: &HashMap<Vec<usize>, Algorithm>) -> String {
+    let mut cases = data.iter().collect::<Vec<_>>();
+    cases.sort_by_key(|(key, _)| step.order(key));
+    write_file(cases.into_iter().map(|(key, algorithm)| NamedCase {
+        name: step.name(key).to_string(),
+        algorithm: algorithm.clone(),
+    }))
+}
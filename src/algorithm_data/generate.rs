@@ -0,0 +1,253 @@
+// searches for the algorithms of the last layer files (oll.json, pll.json, coll.json,
+// zbll.json, wv.json and vls.json): every case of a step (up to the turns of the last
+// layer the step allows) is solved with the moves of a set, meeting in the middle: the
+// end of an algorithm comes from a table of the states which a few moves take to solved,
+// the last layer may be turned before the moves of the algorithm without counting them
+// (as the solver turns it), only the turns which the layer needs after them are left out
+
+use super::write_last_layer;
+use crate::cube::Cube;
+use crate::cube::algorithm::{Algorithm, Move, MoveType, Metric};
+use crate::cube::last_layer::{auf, Step, DATA_FRAME};
+use crate::cube::packed::PackedCube;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Error};
+
+// the length of the table's algorithms, it gets big quickly
+const MAX_TABLE_LENGTH: usize = 7;
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Generator {
+    step: Step,
+    // in the data's frame
+    move_types: Vec<MoveType>,
+    max_length: usize,
+    alternatives: usize,
+}
+
+// the found algorithms of every case, shortest first, an empty list when there was
+// none short enough
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Generated {
+    pub step: Step,
+    pub max_length: usize,
+    pub cases: Vec<(Vec<usize>, Vec<Algorithm>)>,
+}
+
+// every case of the step: all its states, grouped by the aufs which turn them into each
+// other, as keys of the named case of each group
+pub(crate) fn cases(step: Step) -> Vec<Vec<usize>> {
+    let found = step.groups().iter()
+        .map(|variants| {
+            step.named_key(&variants[0])
+                .unwrap_or_else(|| panic!("no name for the case of\n{:?}", variants[0]))
+        })
+        .collect::<HashSet<_>>();
+    step.keys().into_iter().filter(|key| found.contains(key)).collect()
+}
+
+// no move of the same face twice in a row, and moves of opposite faces (which commute)
+// in one order only, the table's algorithms are searched backwards
+fn follows(last: Option<&Move>, next: &Move, backwards: bool) -> bool {
+    match last {
+        None => true,
+        Some(last) if last.face() == next.face() => false,
+        Some(last) if last.face() == next.face().opposite() => (last.face() < next.face()) != backwards,
+        _ => true,
+    }
+}
+
+fn is_canonical(moves: &[Move]) -> bool {
+    moves.windows(2).all(|w| follows(Some(&w[0]), &w[1], false))
+}
+
+// the turn of the last layer an algorithm starts with, which the length cap doesn't count
+fn pre_auf(algorithm: &Algorithm) -> Option<&Move> {
+    algorithm.into_iter().next().filter(|m| m.move_type() == MoveType::U)
+}
+
+#[allow(dead_code)]
+impl Generator {
+    // <R, U, F>, up to 14 moves and 5 alternatives
+    pub fn new(step: Step) -> Self {
+        Self { step, move_types: vec![MoveType::R, MoveType::U, MoveType::F], max_length: 14, alternatives: 5 }
+    }
+
+    pub fn with_moves(self, move_types: &[MoveType]) -> Self {
+        Self { move_types: move_types.to_vec(), ..self }
+    }
+
+    pub fn with_max_length(self, max_length: usize) -> Self {
+        Self { max_length, ..self }
+    }
+
+    pub fn with_alternatives(self, alternatives: usize) -> Self {
+        Self { alternatives, ..self }
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    pub fn move_types(&self) -> &[MoveType] {
+        &self.move_types
+    }
+
+    // the moves with their effect on the cube
+    fn moves(&self) -> Vec<(Move, Cube)> {
+        self.move_types.iter()
+            .flat_map(|t| (1..4).map(move |times| Move::new(*t, times)))
+            .map(|m| {
+                let algorithm = Some(m).into_iter().collect::<Algorithm>();
+                (m, Cube::from_algorithm(&algorithm.symmetric(&DATA_FRAME)))
+            })
+            .collect()
+    }
+
+    // what's compared: the whole cube for pll and zbll, the corners and the orientation
+    // for coll, the orientation for the others
+    fn state(&self, cube: Cube) -> PackedCube {
+        match self.step {
+            Step::Oll | Step::Wv | Step::Vls => cube.last_layer_orientation().packed(),
+            Step::Coll => cube.last_layer_corners().packed(),
+            Step::Pll | Step::Zbll => cube.packed(),
+        }
+    }
+
+    // the algorithms of at most length moves which solve each state of the table
+    fn table(&self, length: usize) -> HashMap<PackedCube, Vec<Vec<Move>>> {
+        fn search(
+            generator: &Generator,
+            moves: &[(Move, Cube)],
+            cube: Cube,
+            path: &mut Vec<Move>,
+            length: usize,
+            table: &mut HashMap<PackedCube, Vec<Vec<Move>>>,
+        ) {
+            // path leads from the solved state to cube, so its inverse solves it
+            let solution = path.iter().rev().map(|m| Move::new(m.move_type(), 4 - m.times())).collect();
+            table.entry(generator.state(cube)).or_default().push(solution);
+            if path.len() == length { return }
+            for (m, effect) in moves {
+                if !follows(path.last(), m, true) { continue }
+                path.push(*m);
+                search(generator, moves, cube * *effect, path, length, table);
+                path.pop();
+            }
+        }
+
+        let solved = match self.step {
+            Step::Oll | Step::Wv | Step::Vls => vec![Cube::solved()],
+            Step::Pll | Step::Coll | Step::Zbll => (0..4).map(|t| Cube::from_algorithm(&auf(t))).collect(),
+        };
+        let mut table = HashMap::new();
+        for cube in solved {
+            search(self, &self.moves(), cube, &mut vec![], length, &mut table);
+        }
+        table
+    }
+
+    pub fn generate(&self) -> Generated {
+        let keys = cases(self.step);
+        let states = keys.iter().map(|key| self.step.case(key)).collect::<Vec<_>>();
+        let cases = keys.into_iter().zip(self.solve(&states)).collect();
+        Generated { step: self.step, max_length: self.max_length, cases }
+    }
+
+    // the found algorithms of each state (compared as the step compares them), shortest
+    // first (not counting the turn of the last layer they may start with), they end with
+    // the last layer turned any way for pll and zbll
+    pub fn solve(&self, states: &[Cube]) -> Vec<Vec<Algorithm>> {
+        let moves = self.moves();
+        let table_length = (self.max_length / 2).min(MAX_TABLE_LENGTH);
+        let table = self.table(table_length);
+        let last_layer = MoveType::U;
+        // the last slot is turned to the slot of the data instead, a turn of the last
+        // layer is part of the algorithm there
+        let pre_aufs = match self.step {
+            Step::Wv | Step::Vls => vec![],
+            _ => (1..4).map(|t| (Move::new(last_layer, t), Cube::from_algorithm(&auf(t)))).collect::<Vec<_>>(),
+        };
+
+        states.iter()
+            .map(|state| {
+                let mut found = vec![];
+                // every algorithm is split once: as much as possible comes from the table
+                let mut search = |pre_auf: &[Move], path: &[Move], cube: Cube| {
+                    for end in table.get(&self.state(cube)).into_iter().flatten() {
+                        if end.len() < table_length && !path.is_empty() { continue }
+                        let moves = pre_auf.iter().chain(path).chain(end).copied().collect::<Vec<_>>();
+                        // the last layer can be turned for free afterwards
+                        if is_canonical(&moves) && moves.last().map(Move::move_type) != Some(last_layer) {
+                            found.push(moves);
+                        }
+                    }
+                };
+                // the pre auf is the only turn of the last layer an algorithm starts with
+                let mut stack = Some((vec![], vec![], *state)).into_iter()
+                    .chain(pre_aufs.iter().map(|(m, effect)| (vec![*m], vec![], *state * *effect)))
+                    .collect::<Vec<_>>();
+                while let Some((pre_auf, path, cube)) = stack.pop() {
+                    search(&pre_auf, &path, cube);
+                    if path.len() + table_length >= self.max_length { continue }
+                    for (m, effect) in &moves {
+                        if !follows(path.last().or_else(|| pre_auf.last()), m, false) { continue }
+                        if path.is_empty() && !pre_aufs.is_empty() && m.move_type() == last_layer { continue }
+                        let mut next = path.clone();
+                        next.push(*m);
+                        stack.push((pre_auf.clone(), next, cube * *effect));
+                    }
+                }
+
+                let mut algorithms = found.into_iter()
+                    .map(|moves| moves.into_iter().collect::<Algorithm>())
+                    .collect::<Vec<_>>();
+                // the ones without a pre auf first when the rest is as long
+                algorithms.sort_by_cached_key(|a| {
+                    (a.len() - pre_auf(a).iter().count(), a.len(), a.length(Metric::Qtm), format!("{:?}", a))
+                });
+                algorithms.dedup();
+                // nothing beats doing nothing for the skips
+                if algorithms.first().map(Algorithm::is_empty) == Some(true) { algorithms.truncate(1) }
+                algorithms.truncate(self.alternatives);
+                algorithms
+            })
+            .collect()
+    }
+}
+
+#[allow(dead_code)]
+impl Generated {
+    // the first algorithm of every case which has one
+    pub fn data(&self) -> HashMap<Vec<usize>, Algorithm> {
+        self.cases.iter()
+            .filter_map(|(key, algorithms)| Some((key.clone(), algorithms.first()?.clone())))
+            .collect()
+    }
+
+    // the data file of the step
+    pub fn to_json(&self) -> String {
+        write_last_layer(self.step, &self.data())
+    }
+}
+
+// the alternatives of every case
+impl Display for Generated {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        for (key, algorithms) in &self.cases {
+            writeln!(f, "{}:", self.step.name(key))?;
+            if algorithms.is_empty() {
+                writeln!(f, "    nothing up to {} moves", self.max_length)?;
+            }
+            for algorithm in algorithms {
+                match pre_auf(algorithm) {
+                    Some(m) => writeln!(f, "    {:?} ({} + {})", algorithm, m, algorithm.len() - 1)?,
+                    None => writeln!(f, "    {:?} ({})", algorithm, algorithm.len())?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+// the text format the data used to be in, kept to migrate files to format.rs
+//
+// cross.txt and f2l.txt: a line per position, written with face digits ("01" for an
+// edge, "01212" for a corner and an edge), then ':' and algorithms in quotes, each
+// preceded by the solved pieces it needs (none for the last one, which is the default),
+// e.g. 01:0204"L2 U' F2""D", lines starting with "//" are comments and "///" ends
+// the file
+//
+// oll.txt and pll.txt: a line per case, its key as comma separated numbers, ':' and
+// the algorithm in quotes
+
+use super::{PieceKey, CEPosition, DataSet, format};
+use crate::cube::piece::position::EdgePosition;
+use crate::cube::algorithm::Algorithm;
+use crate::support::{Tern, IndexOf};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+fn split_at_first(s: &str, c: char) -> (&str, &str) {
+    let mut iter = s.splitn(2, c);
+    let exp = &format!("failed to split at first '{}'", c);
+    (iter.next().expect(exp), iter.next().expect(exp))
+}
+
+fn safe_insert<K: Eq + Hash, V: Debug>(map: &mut HashMap<K, V>, k: K, v: V) {
+    if let Some(prev) = map.insert(k, v) {
+        panic!("Duplicate key in map, previous value: {:?}", prev)
+    }
+}
+
+fn load1<K>(text: &str) -> HashMap<K, Tern<Vec<K>, Algorithm>> where
+    K: PieceKey + Eq + Hash + Debug
+{
+    let mut map = HashMap::new();
+
+    for line in text.lines() {
+        if line == "///" { break }
+        if line.starts_with("//") { continue }
+
+        let (pieces, mut line) = split_at_first(line, ':');
+        let pieces = K::from_char_iter(pieces.chars());
+
+        let mut tern_vec =
+            Vec::<(Vec<K>, Algorithm)>::with_capacity(10);
+        while !line.is_empty() {
+            let mut predicates = vec![];
+            while line.chars().nth(0).unwrap().is_numeric() {
+                let (pred, rest) = line.split_at(K::LENGTH);
+                line = rest;
+                predicates.push(K::from_char_iter(pred.chars()))
+            }
+
+            let end = line.index_of('"', 1)
+                .expect("line ended unexpectedly");
+            let (alg, rest) = line.split_at(end + 1);
+            line = rest;
+            let alg = Algorithm::from(&alg[1..alg.len() - 1]);
+
+            tern_vec.push((predicates, alg));
+        }
+
+        let mut iter = tern_vec.into_iter().rev();
+        let mut tern = Tern::End(iter.next().expect("Unexpected empty condition vector").1);
+        for (con, alg) in iter {
+            tern = Tern::Con(con, alg, Box::new(tern));
+        }
+
+        safe_insert(&mut map, pieces, tern)
+    }
+
+    map
+}
+
+fn load2(text: &str) -> HashMap<Vec<usize>, Algorithm> {
+    let mut map = HashMap::new();
+
+    for line in text.lines() {
+        let (pred, alg) = split_at_first(line, ':');
+        let pred = pred.split(',')
+            .map(|i| i.parse().expect(&format!("Invalid value for usize: {}" , i)))
+            .collect();
+        let alg = Algorithm::from(&alg[1..alg.len() - 1]);
+        safe_insert(&mut map, pred, alg);
+    }
+
+    map
+}
+
+
+// the text of a file in the old format, in the new one
+pub fn convert(set: DataSet, text: &str) -> String {
+    match set {
+        DataSet::Cross => format::write_tern(&load1::<EdgePosition>(text)),
+        DataSet::F2l => format::write_tern(&load1::<CEPosition>(text)),
+        _ => format::write_last_layer(set.step().unwrap(), &load2(text)),
+    }
+}
+
+// writes a .json file next to every .txt data file of the directory, returns the
+// written files
+#[allow(dead_code)]
+pub fn convert_dir(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut written = vec![];
+    for set in DataSet::ALL.iter() {
+        let old = dir.join(format!("{}.txt", set.name()));
+        if !old.exists() { continue }
+        let new = dir.join(set.file_name());
+        fs::write(&new, convert(*set, &fs::read_to_string(&old)?))?;
+        written.push(new);
+    }
+    Ok(written)
+}
@@ -4,82 +4,64 @@ extern crate serde_json;
 
 use std::collections::HashMap;
 use crate::support::Tern;
+use crate::cube::piece::face::Face;
 use crate::cube::piece::position::{EdgePosition, CornerPosition};
-use crate::cube::algorithm::Algorithm;
-use std::path::Path;
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use crate::support::IndexOf;
-use std::hash::Hash;
-use std::fmt::Debug;
-use std::mem;
-
-fn iter_lines<P: AsRef<Path>>(path: P) -> impl Iterator<Item=String> {
-    let file = File::open(path).expect("failed to open file");
-    let reader = BufReader::new(file);
-    reader.lines().map(|l| l.expect("failed to read line"))
-}
-
-fn split_at_first(s: &str, c: char) -> (&str, &str) {
-    let mut iter = s.splitn(2, c);
-    let exp = &format!("failed to split at first '{}'", c);
-    (iter.next().expect(exp), iter.next().expect(exp))
-}
-
-fn safe_insert<K: Eq + Hash, V: Debug>(map: &mut HashMap<K, V>, k: K, v: V) {
-    if let Some(prev) = map.insert(k, v) {
-        panic!("Duplicate key in map, previous value: {:?}", prev)
-    }
-}
+use crate::cube::algorithm::{Algorithm, MoveType};
+use crate::cube::last_layer::{Step, DATA_FRAME};
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::env;
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+mod coverage;
+mod format;
+mod generate;
+mod legacy;
+mod verify;
 
-pub trait PieceKey {
+#[allow(unused_imports)]
+pub use coverage::{coverage, Coverage, CoverageCase};
+#[allow(unused_imports)]
+pub use format::{DataError, Overrides, VERSION};
+#[allow(unused_imports)]
+pub(crate) use format::{load_tern, load_last_layer, load_overrides, write_tern, write_last_layer};
+#[allow(unused_imports)]
+pub use generate::{Generator, Generated};
+#[allow(unused_imports)]
+pub(crate) use generate::cases;
+#[allow(unused_imports)]
+pub use legacy::{convert, convert_dir};
+#[allow(unused_imports)]
+pub use verify::{verify, verify_all, Finding, Problem, Report};
+#[allow(unused_imports)]
+pub(crate) use verify::{verify_cross, verify_f2l, verify_last_layer, last_layer_problem};
+
+pub trait PieceKey: Sized {
     const LENGTH: usize;
 
     fn from_char_iter(iter: impl Iterator<Item=char>) -> Self;
-}
 
-pub(crate) fn load1<P: AsRef<Path>, K>(path: P)
-                                       -> HashMap<K, Tern<Vec<K>, Algorithm>> where
-    K: PieceKey + Eq + Hash + Debug
-{
-    let mut map = HashMap::new();
-
-    for line in iter_lines(path) {
-        if &line == "///" { break }
-        if line.starts_with("//") { continue }
-
-        let (pieces, mut line) = split_at_first(&line, ':');
-        let pieces = K::from_char_iter(pieces.chars());
-
-        let mut tern_vec =
-            Vec::<(Vec<K>, Algorithm)>::with_capacity(10);
-        while !line.is_empty() {
-            let mut predicates = vec![];
-            while line.chars().nth(0).unwrap().is_numeric() {
-                let (pred, rest) = line.split_at(K::LENGTH);
-                line = rest;
-                predicates.push(K::from_char_iter(pred.chars()))
-            }
-
-            let end = line.index_of('"', 1)
-                .expect("line ended unexpectedly");
-            let (alg, rest) = line.split_at(end + 1);
-            line = rest;
-            let alg = Algorithm::from(&alg[1..alg.len() - 1]);
-
-            tern_vec.push((predicates, alg));
-        }
+    // the letters of the faces, None unless they make a position
+    fn from_letters(s: &str) -> Option<Self>;
 
-        let mut iter = tern_vec.into_iter().rev();
-        let mut tern = Tern::End(iter.next().expect("Unexpected empty condition vector").1);
-        for (con, alg) in iter {
-            tern = Tern::Con(con, alg, Box::new(tern));
-        }
+    fn letters(&self) -> String;
+}
 
-        safe_insert(&mut map, pieces, tern)
-    }
+// faces are written as in the algorithms of the data, which is turned (see DATA_FRAME)
+fn face_letter(face: Face) -> char {
+    format!("{:?}", MoveType::from(DATA_FRAME.inverse().image(face))).chars().next().unwrap()
+}
 
-    map
+// the faces of a piece, all adjacent to each other
+fn letter_faces(s: &str, count: usize) -> Option<Vec<Face>> {
+    let faces = s.chars()
+        .map(|c| MoveType::parse(c).ok().map(|t| DATA_FRAME.image(t.face())))
+        .collect::<Option<Vec<_>>>()?;
+    let adjacent = faces.iter()
+        .enumerate()
+        .all(|(i, f)| faces[..i].iter().all(|g| *g != *f && *g != f.opposite()));
+    if faces.len() == count && adjacent { Some(faces) } else { None }
 }
 
 impl PieceKey for EdgePosition {
@@ -88,10 +70,19 @@ impl PieceKey for EdgePosition {
     fn from_char_iter(iter: impl Iterator<Item=char>) -> Self {
         iter.collect()
     }
+
+    fn from_letters(s: &str) -> Option<Self> {
+        letter_faces(s, 2).map(|f| f.into_iter().collect())
+    }
+
+    fn letters(&self) -> String {
+        [self.0, self.1].iter().map(|f| face_letter(*f)).collect()
+    }
 }
 
-type CEPosition = (CornerPosition, EdgePosition);
+pub type CEPosition = (CornerPosition, EdgePosition);
 
+// "DFR FR"
 impl PieceKey for CEPosition {
     const LENGTH: usize = 5;
 
@@ -100,46 +91,186 @@ impl PieceKey for CEPosition {
         let e = iter.collect();
         (c, e)
     }
+
+    fn from_letters(s: &str) -> Option<Self> {
+        let mut parts = s.split(' ');
+        let corner = letter_faces(parts.next()?, 3)?.into_iter().collect();
+        let edge = EdgePosition::from_letters(parts.next()?)?;
+        if parts.next().is_some() { return None }
+        Some((corner, edge))
+    }
+
+    fn letters(&self) -> String {
+        let (corner, edge) = self;
+        let corner = [corner.0, corner.1, corner.2].iter().map(|f| face_letter(*f)).collect::<String>();
+        format!("{} {}", corner, edge.letters())
+    }
 }
 
-fn load2<P: AsRef<Path>>(path: P) -> HashMap<Vec<usize>, Algorithm> {
-    let mut map = HashMap::new();
+// the data files, in the format of format.rs
+#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DataSet {
+    Cross,
+    F2l,
+    Oll,
+    Pll,
+    Coll,
+    Zbll,
+    Wv,
+    Vls,
+}
+
+impl DataSet {
+    pub const ALL: [DataSet; 8] = [
+        DataSet::Cross, DataSet::F2l, DataSet::Oll, DataSet::Pll,
+        DataSet::Coll, DataSet::Zbll, DataSet::Wv, DataSet::Vls,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DataSet::Cross => "cross",
+            DataSet::F2l => "f2l",
+            DataSet::Oll => "oll",
+            DataSet::Pll => "pll",
+            DataSet::Coll => "coll",
+            DataSet::Zbll => "zbll",
+            DataSet::Wv => "wv",
+            DataSet::Vls => "vls",
+        }
+    }
 
-    for line in iter_lines(path) {
-        let (pred, alg) = split_at_first(&line, ':');
-        let pred = pred.split(',')
-            .map(|i| i.parse().expect(&format!("Invalid value for usize: {}" , i)))
-            .collect();
-        let alg = Algorithm::from(&alg[1..alg.len() - 1]);
-        safe_insert(&mut map, pred, alg);
+    // the step of the last layer (or slot) whose algorithms are in the file
+    pub fn step(self) -> Option<Step> {
+        match self {
+            DataSet::Cross | DataSet::F2l => None,
+            DataSet::Oll => Some(Step::Oll),
+            DataSet::Pll => Some(Step::Pll),
+            DataSet::Coll => Some(Step::Coll),
+            DataSet::Zbll => Some(Step::Zbll),
+            DataSet::Wv => Some(Step::Wv),
+            DataSet::Vls => Some(Step::Vls),
+        }
     }
 
-    map
+    pub fn file_name(self) -> String {
+        format!("{}.json", self.name())
+    }
 }
 
 
 // Data:
-fn lazy_get<'a, T>(data: &'a mut Option<T>, load: fn(&'a str) -> T, path: &'a str) -> &'a T {
-    if data.is_none() {
-        mem::replace(data, Some(load(path)));
+// the data files are embedded, a directory with files of the same names replaces them
+// (the missing ones stay embedded), it's taken from this variable or set_data_dir
+pub const DATA_DIR_VAR: &str = "RUSTIKS_DATA_DIR";
+// an override file (see format.rs) replaces some oll and pll algorithms, it's taken from
+// this variable (with set_overrides_from_var, as main does) or set_overrides
+pub const OVERRIDES_VAR: &str = "RUSTIKS_OVERRIDES";
+
+struct DataDir {
+    dir: Option<PathBuf>,
+    // already checked, a broken file is never set
+    overrides: Option<Overrides>,
+    // no more changes once data has been loaded
+    used: bool,
+}
+
+lazy_static! {
+    static ref DATA_DIR: Mutex<DataDir> = Mutex::new(DataDir {
+        dir: env::var_os(DATA_DIR_VAR).map(PathBuf::from),
+        overrides: None,
+        used: false,
+    });
+}
+
+#[allow(dead_code)]
+pub fn set_data_dir(dir: impl Into<PathBuf>) {
+    let mut data_dir = DATA_DIR.lock().unwrap();
+    assert!(!data_dir.used, "algorithm data has already been loaded");
+    data_dir.dir = Some(dir.into());
+}
+
+// the file is read and checked right away, so that nothing is loaded with a broken one
+#[allow(dead_code)]
+pub fn set_overrides(file: impl Into<PathBuf>) -> Result<(), DataError> {
+    let overrides = read_overrides(&file.into())?;
+    let mut data_dir = DATA_DIR.lock().unwrap();
+    assert!(!data_dir.used, "algorithm data has already been loaded");
+    data_dir.overrides = Some(overrides);
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn set_overrides_from_var() -> Result<(), DataError> {
+    env::var_os(OVERRIDES_VAR).map_or(Ok(()), set_overrides)
+}
+
+fn read_overrides(path: &Path) -> Result<Overrides, DataError> {
+    let file = path.display().to_string();
+    let text = fs::read_to_string(path)
+        .map_err(|e| DataError { file: file.clone(), line: 0, column: 0, message: e.to_string() })?;
+    load_overrides(&file, &text)
+}
+
+// the text of a data file and where it comes from
+fn data_text(set: DataSet, embedded: &'static str) -> (String, Cow<'static, str>) {
+    let mut data_dir = DATA_DIR.lock().unwrap();
+    data_dir.used = true;
+    match &data_dir.dir {
+        Some(dir) if dir.join(set.file_name()).exists() => {
+            let path = dir.join(set.file_name());
+            let text = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+            (path.display().to_string(), Cow::Owned(text))
+        }
+        _ => (format!("data/{}", set.file_name()), Cow::Borrowed(embedded)),
     }
-    data.as_ref().unwrap()
+}
+
+lazy_static! {
+    static ref OVERRIDES: Overrides = {
+        let mut data_dir = DATA_DIR.lock().unwrap();
+        data_dir.used = true;
+        data_dir.overrides.clone().unwrap_or_default()
+    };
+}
+
+// the personal algorithms in use, which are already in oll_data and pll_data
+#[allow(dead_code)]
+pub(crate) fn overrides() -> &'static Overrides {
+    &OVERRIDES
 }
 
 macro_rules! lazy_load {
-    ($const:ident, $fn:ident, $load:expr, $path:expr, $type:ty) => {
-        static mut $const: Option<$type> = None;
+    ($const:ident, $fn:ident, $set:expr, $file:expr, $load:expr, $type:ty) => {
+        lazy_static! {
+            static ref $const: $type = {
+                let (file, text) = data_text($set, include_str!(concat!("data/", $file)));
+                $load(&file, &text).unwrap_or_else(|e| panic!("{}", e))
+            };
+        }
+
         pub(crate) fn $fn() -> &'static $type {
-            unsafe { lazy_get(&mut $const, $load, $path) }
+            &$const
         }
     };
 }
 
-lazy_load!(CROSS_DATA, cross_data, load1, "src/algorithm_data/data/cross.txt",
+lazy_load!(CROSS_DATA, cross_data, DataSet::Cross, "cross.json", format::load_tern,
     HashMap<EdgePosition, Tern<Vec<EdgePosition>, Algorithm>>);
-lazy_load!(F2L_DATA, f2l_data, load1, "src/algorithm_data/data/f2l.txt",
+lazy_load!(F2L_DATA, f2l_data, DataSet::F2l, "f2l.json", format::load_tern,
     HashMap<CEPosition, Tern<Vec<CEPosition>, Algorithm>>);
-lazy_load!(OLL_DATA, oll_data, load2, "src/algorithm_data/data/oll.txt",
+lazy_load!(OLL_DATA, oll_data, DataSet::Oll, "oll.json",
+    |file, text| format::load_last_layer(Step::Oll, file, text).map(|data| overrides().apply(Step::Oll, data)),
+    HashMap<Vec<usize>, Algorithm>);
+lazy_load!(PLL_DATA, pll_data, DataSet::Pll, "pll.json",
+    |file, text| format::load_last_layer(Step::Pll, file, text).map(|data| overrides().apply(Step::Pll, data)),
     HashMap<Vec<usize>, Algorithm>);
-lazy_load!(PLL_DATA, pll_data, load2, "src/algorithm_data/data/pll.txt",
-    HashMap<Vec<usize>, Algorithm>);
\ No newline at end of file
+lazy_load!(COLL_DATA, coll_data, DataSet::Coll, "coll.json",
+    |file, text| format::load_last_layer(Step::Coll, file, text), HashMap<Vec<usize>, Algorithm>);
+lazy_load!(ZBLL_DATA, zbll_data, DataSet::Zbll, "zbll.json",
+    |file, text| format::load_last_layer(Step::Zbll, file, text), HashMap<Vec<usize>, Algorithm>);
+lazy_load!(WV_DATA, wv_data, DataSet::Wv, "wv.json",
+    |file, text| format::load_last_layer(Step::Wv, file, text), HashMap<Vec<usize>, Algorithm>);
+lazy_load!(VLS_DATA, vls_data, DataSet::Vls, "vls.json",
+    |file, text| format::load_last_layer(Step::Vls, file, text), HashMap<Vec<usize>, Algorithm>);
@@ -0,0 +1,463 @@
+// checks the entries of the data files: the case of every entry is built as the entry
+// describes it (for cross.json and f2l.json with every combination of the other
+// pieces being solved, which picks the branch), its algorithm is applied and the
+// stage has to be solved; only the pieces an entry is about are placed, the others
+// don't matter to its algorithm
+
+use super::{cross_data, f2l_data, CEPosition, DataSet, PieceKey};
+use crate::cube::{Cube, Edge, Corner};
+use crate::cube::algorithm::{Algorithm, Move, MoveType};
+use crate::cube::last_layer::{auf, first_two_layers_solved, Step, DATA_FRAME};
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::{EdgePosition, CornerPosition};
+use crate::cube::solving::{is_edge_solved, is_corner_solved};
+use crate::support::Tern;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter, Error};
+use std::hash::Hash;
+
+#[allow(dead_code)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Problem {
+    // the algorithm doesn't solve the case, with what's wrong after it
+    Broken(String),
+    // a branch which is never picked, with why
+    Unused(String),
+    // a case without an entry
+    Missing,
+    // a shorter algorithm which does the same
+    Suboptimal(Algorithm),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub set: DataSet,
+    // the position or the name of the case, as in the file
+    pub case: String,
+    // the other pieces which are solved in the case
+    pub solved: Vec<String>,
+    pub algorithm: Algorithm,
+    pub problem: Problem,
+}
+
+impl Finding {
+    pub fn is_suboptimal(&self) -> bool {
+        matches!(self.problem, Problem::Suboptimal(_))
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}: {}", self.set.file_name(), self.case)?;
+        if !self.solved.is_empty() {
+            write!(f, " with {} solved", self.solved.join(", "))?;
+        }
+        match &self.problem {
+            Problem::Missing => write!(f, ": missing"),
+            Problem::Broken(what) => write!(f, ": \"{:?}\" is broken, {}", self.algorithm, what),
+            Problem::Unused(why) => write!(f, ": \"{:?}\" is never used, {}", self.algorithm, why),
+            Problem::Suboptimal(shorter) => write!(
+                f, ": \"{:?}\" is suboptimal, \"{:?}\" ({} moves) does the same",
+                self.algorithm, shorter, shorter.len(),
+            ),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Default, Debug)]
+pub struct Report {
+    // the number of entries
+    pub entries: usize,
+    pub findings: Vec<Finding>,
+}
+
+#[allow(dead_code)]
+impl Report {
+    // everything but the suboptimal entries
+    pub fn broken(&self) -> Vec<&Finding> {
+        self.findings.iter().filter(|f| !f.is_suboptimal()).collect()
+    }
+
+    pub fn suboptimal(&self) -> Vec<&Finding> {
+        self.findings.iter().filter(|f| f.is_suboptimal()).collect()
+    }
+
+    pub fn merge(mut self, other: Report) -> Self {
+        self.entries += other.entries;
+        self.findings.extend(other.findings);
+        self
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        for finding in self.broken().into_iter().chain(self.suboptimal()) {
+            writeln!(f, "{}", finding)?;
+        }
+        write!(
+            f, "{} entries, {} broken, {} suboptimal",
+            self.entries, self.broken().len(), self.suboptimal().len(),
+        )
+    }
+}
+
+// the 18 moves
+fn moves() -> Vec<Move> {
+    MoveType::ALL.iter()
+        .flat_map(|t| (1..4).map(move |times| Move::new(*t, times)))
+        .collect()
+}
+
+// puts a piece at a position, the piece which was there goes where it was
+fn place_edge(cube: &mut Cube, id: EdgePosition, position: EdgePosition) {
+    let from = cube.iter_edges().find(|e| e.id == id).unwrap().pos;
+    let other = cube.edge_at(position).id;
+    cube.set_edge(&Edge::new(other, from));
+    cube.set_edge(&Edge::new(id, position));
+}
+
+fn place_corner(cube: &mut Cube, id: CornerPosition, position: CornerPosition) {
+    let from = cube.iter_corners().find(|c| c.id == id).unwrap().pos;
+    let other = cube.corner_at(position).id;
+    cube.set_corner(&Corner::new(other, from));
+    cube.set_corner(&Corner::new(id, position));
+}
+
+// every way a piece can be placed, as it's written in the keys
+fn edge_positions(id: EdgePosition) -> Vec<EdgePosition> {
+    Cube::solved().iter_edges()
+        .flat_map(|e| vec![e.pos, EdgePosition(e.pos.1, e.pos.0)])
+        .map(|position| {
+            let mut cube = Cube::solved();
+            place_edge(&mut cube, id, position);
+            let placed = cube.iter_edges().find(|e| e.id == id).unwrap().pos;
+            placed
+        })
+        .collect()
+}
+
+fn corner_positions(id: CornerPosition) -> Vec<CornerPosition> {
+    Cube::solved().iter_corners()
+        .flat_map(|c| (0..3).map(move |twist| (c.pos, twist)))
+        .map(|(position, twist)| {
+            let mut cube = Cube::solved();
+            place_corner(&mut cube, id, position);
+            if twist > 0 { cube.rotate_corner_clockwise_illegal(position, twist) }
+            let placed = cube.iter_corners().find(|c| c.id == id).unwrap().pos;
+            placed
+        })
+        .collect()
+}
+
+// what a file of cases with branches is about
+struct Stage<K> {
+    set: DataSet,
+    // the piece (or pair) which the algorithms solve
+    target: K,
+    // the pieces of the conditions, in the order in which the solver lists them
+    others: [K; 3],
+    // every position of the target
+    keys: Vec<K>,
+    // puts the target at a position
+    place: fn(&mut Cube, K),
+    is_solved: fn(&Cube, K) -> bool,
+    // what has to be solved in every case (the cross for f2l)
+    base: fn(&Cube) -> bool,
+}
+
+fn cross_stage() -> Stage<EdgePosition> {
+    Stage {
+        set: DataSet::Cross,
+        target: pos!(0, 5),
+        others: [pos!(0, 1), pos!(0, 2), pos!(0, 4)],
+        keys: edge_positions(pos!(0, 5)),
+        place: |cube, key| place_edge(cube, pos!(0, 5), key),
+        is_solved: is_edge_solved,
+        base: |_| true,
+    }
+}
+
+fn f2l_stage() -> Stage<CEPosition> {
+    let corners = corner_positions(pos!(0, 4, 5));
+    // the edge can't be in the cross
+    let edges = edge_positions(pos!(4, 5)).into_iter()
+        .filter(|e| e.0 != Face::new(0) && e.1 != Face::new(0))
+        .collect::<Vec<_>>();
+    Stage {
+        set: DataSet::F2l,
+        target: (pos!(0, 4, 5), pos!(4, 5)),
+        others: [(pos!(0, 1, 2), pos!(1, 2)), (pos!(0, 1, 5), pos!(1, 5)), (pos!(0, 2, 4), pos!(2, 4))],
+        keys: corners.iter().flat_map(|c| edges.iter().map(move |e| (*c, *e))).collect(),
+        place: |cube, (corner, edge)| {
+            place_corner(cube, pos!(0, 4, 5), corner);
+            place_edge(cube, pos!(4, 5), edge);
+        },
+        is_solved: |cube, (corner, edge)| is_corner_solved(cube, corner) && is_edge_solved(cube, edge),
+        base: |cube| [pos!(0, 1), pos!(0, 2), pos!(0, 4), pos!(0, 5)].iter().all(|e| is_edge_solved(cube, *e)),
+    }
+}
+
+// the shortest cross algorithms, by a breadth first search over the positions of some
+// edges, the state is the indices of their positions in base 24
+struct CrossDistances {
+    moves: Vec<Move>,
+    positions: Vec<EdgePosition>,
+    // the index of the position after each move
+    next: Vec<Vec<usize>>,
+    // u8::MAX for unreachable states
+    distances: Vec<u8>,
+}
+
+impl CrossDistances {
+    // the edges are solved at their own positions
+    fn new(edges: &[EdgePosition]) -> Self {
+        let moves = moves();
+        let positions = edge_positions(pos!(0, 5));
+        let next = positions.iter()
+            .map(|position| moves.iter().map(|m| {
+                let mut cube = Cube::solved();
+                place_edge(&mut cube, pos!(0, 5), *position);
+                cube.apply(&Some(*m).into_iter().collect());
+                let moved = cube.iter_edges().find(|e| e.id == pos!(0, 5)).unwrap().pos;
+                positions.iter().position(|p| *p == moved).unwrap()
+            }).collect())
+            .collect();
+        let mut search = Self { moves, positions, next, distances: vec![u8::MAX; 24usize.pow(edges.len() as u32)] };
+
+        let solved = search.state(edges);
+        search.distances[index(&solved)] = 0;
+        let mut queue = VecDeque::from(vec![solved]);
+        while let Some(state) = queue.pop_front() {
+            let distance = search.distances[index(&state)];
+            for i in 0..search.moves.len() {
+                let moved = search.moved(&state, i);
+                if search.distances[index(&moved)] == u8::MAX {
+                    search.distances[index(&moved)] = distance + 1;
+                    queue.push_back(moved);
+                }
+            }
+        }
+        search
+    }
+
+    fn state(&self, positions: &[EdgePosition]) -> Vec<usize> {
+        positions.iter().map(|p| self.positions.iter().position(|q| q == p).unwrap()).collect()
+    }
+
+    fn moved(&self, state: &[usize], i: usize) -> Vec<usize> {
+        state.iter().map(|p| self.next[*p][i]).collect()
+    }
+
+    // a shortest algorithm for the edges of a case, in the data's frame
+    fn solution(&self, case: &Cube, edges: &[EdgePosition]) -> Algorithm {
+        let positions = edges.iter()
+            .map(|id| case.iter_edges().find(|e| e.id == *id).unwrap().pos)
+            .collect::<Vec<_>>();
+        let mut state = self.state(&positions);
+        let mut solution = vec![];
+        while self.distances[index(&state)] > 0 {
+            let i = (0..self.moves.len())
+                .find(|i| self.distances[index(&self.moved(&state, *i))] < self.distances[index(&state)])
+                .unwrap();
+            solution.push(self.moves[i]);
+            state = self.moved(&state, i);
+        }
+        solution.into_iter().collect::<Algorithm>().symmetric(&DATA_FRAME.inverse())
+    }
+}
+
+fn index(state: &[usize]) -> usize {
+    state.iter().fold(0, |index, p| index * 24 + p)
+}
+
+// a shorter algorithm which works wherever this one does, leaving out one move or
+// simplifying
+fn shorter(algorithm: &Algorithm, works: impl Fn(&Algorithm) -> bool) -> Option<Algorithm> {
+    let moves = algorithm.into_iter().copied().collect::<Vec<_>>();
+    let simplified = algorithm.simplified();
+    Some(simplified)
+        .filter(|s| s.len() < algorithm.len())
+        .into_iter()
+        .chain((0..moves.len()).map(|i| {
+            moves.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, m)| *m).collect()
+        }))
+        .find(|a| works(a))
+}
+
+fn verify_tern<K: PieceKey + Copy + Eq + Hash>(
+    stage: &Stage<K>,
+    data: &HashMap<K, Tern<Vec<K>, Algorithm>>,
+    mut optimal: impl FnMut(&Cube, &[K], &Algorithm) -> Option<Algorithm>,
+) -> Report {
+    let Stage { set, target, others, place, is_solved, base, .. } = *stage;
+    let mut findings = vec![];
+    let finding = |case: &K, solved: &[K], algorithm: &Algorithm, problem| Finding {
+        set,
+        case: case.letters(),
+        solved: solved.iter().map(|k| k.letters()).collect(),
+        algorithm: algorithm.clone(),
+        problem,
+    };
+
+    let mut keys = stage.keys.clone();
+    keys.sort_by_key(|k| k.letters());
+    for key in &keys {
+        let tern = match data.get(key) {
+            Some(tern) => tern,
+            None if *key == target => continue,
+            None => {
+                findings.push(finding(key, &[], &Algorithm::from(""), Problem::Missing));
+                continue;
+            }
+        };
+
+        let mut used = vec![];
+        let mut reported = vec![];
+        // the solver lists the solved pieces in the order of others
+        for solved in (0..8).map(|bits| (0..3).filter(|i| bits & (1 << i) != 0).map(|i| others[i]).collect::<Vec<_>>()) {
+            let mut case = Cube::solved();
+            place(&mut case, *key);
+            // the target is where one of the pieces should be
+            if !base(&case) || !solved.iter().all(|k| is_solved(&case, *k)) { continue }
+
+            let works = |algorithm: &Algorithm| {
+                let after = case * Cube::from_algorithm(&algorithm.symmetric(&DATA_FRAME));
+                base(&after) && is_solved(&after, target) && solved.iter().all(|k| is_solved(&after, *k))
+            };
+            // the last algorithm is meant for when nothing else is solved, for the
+            // combinations without a branch the solver tries the others too
+            let results = tern.results();
+            let chosen = tern.eval(&solved);
+            let listed = solved.is_empty() || !std::ptr::eq(chosen, *results.last().unwrap());
+            let algorithm = match listed || works(chosen) {
+                true => chosen,
+                false => results.into_iter().find(|a| works(a)).unwrap_or(chosen),
+            };
+            used.push(algorithm);
+            if !works(algorithm) {
+                let after = case * Cube::from_algorithm(&algorithm.symmetric(&DATA_FRAME));
+                let mut broken = solved.iter()
+                    .filter(|k| !is_solved(&after, **k))
+                    .map(|k| k.letters())
+                    .collect::<Vec<_>>();
+                if !base(&after) { broken.insert(0, "the cross".to_string()) }
+                let what = match (is_solved(&after, target), broken.is_empty()) {
+                    _ if !listed => "none of the algorithms of the case works".to_string(),
+                    (false, true) => format!("it doesn't solve {}", target.letters()),
+                    (false, false) => format!("it doesn't solve {} and breaks {}", target.letters(), broken.join(", ")),
+                    _ => format!("it breaks {}", broken.join(", ")),
+                };
+                findings.push(finding(key, &solved, algorithm, Problem::Broken(what)));
+            } else if !reported.iter().any(|r| std::ptr::eq(*r, algorithm)) {
+                let shorter = optimal(&case, &solved, algorithm).or_else(|| shorter(algorithm, works));
+                if let Some(shorter) = shorter {
+                    reported.push(algorithm);
+                    findings.push(finding(key, &solved, algorithm, Problem::Suboptimal(shorter)));
+                }
+            }
+        }
+
+        let mut seen = vec![];
+        let mut branch = tern;
+        while let Tern::Con(solved, algorithm, next) = branch {
+            if !used.iter().any(|u| std::ptr::eq(*u, algorithm)) {
+                let why = if seen.contains(&solved) {
+                    "an earlier branch has the same solved pieces".to_string()
+                } else if !others.iter().filter(|k| solved.contains(k)).eq(solved.iter()) {
+                    format!("the solved pieces have to be among {} in this order",
+                        others.iter().map(|k| k.letters()).collect::<Vec<_>>().join(", "))
+                } else {
+                    format!("{} can't be there when they're solved", target.letters())
+                };
+                findings.push(finding(key, solved, algorithm, Problem::Unused(why)));
+            }
+            seen.push(solved);
+            branch = next;
+        }
+    }
+    Report { entries: data.len(), findings }
+}
+
+pub(crate) fn verify_cross(data: &HashMap<EdgePosition, Tern<Vec<EdgePosition>, Algorithm>>) -> Report {
+    let stage = cross_stage();
+    let mut searches = HashMap::new();
+    verify_tern(&stage, data, |case, solved, algorithm| {
+        let edges = Some(stage.target).into_iter().chain(solved.iter().copied()).collect::<Vec<_>>();
+        let search = searches.entry(solved.to_vec()).or_insert_with(|| CrossDistances::new(&edges));
+        Some(search.solution(case, &edges)).filter(|s| s.len() < algorithm.len())
+    })
+}
+
+pub(crate) fn verify_f2l(data: &HashMap<CEPosition, Tern<Vec<CEPosition>, Algorithm>>) -> Report {
+    verify_tern(&f2l_stage(), data, |_, _, _| None)
+}
+
+// what's wrong with an algorithm for a case of the last layer (or slot): it has to
+// orient the last layer, coll has to solve its corners and pll and zbll all of it (up
+// to a turn of the layer after the algorithm)
+pub(crate) fn last_layer_problem(step: Step, key: &[usize], algorithm: &Algorithm) -> Option<&'static str> {
+    let after = step.case(key) * Cube::from_algorithm(&algorithm.symmetric(&DATA_FRAME));
+    let turned = (0..4).map(|t| after * Cube::from_algorithm(&auf(t))).collect::<Vec<_>>();
+    let corners_solved = |cube: &Cube| cube.iter_corners().all(|c| c.id == c.pos);
+    if !first_two_layers_solved(&after) {
+        Some("it breaks the first two layers")
+    } else if after.oll_key() != Step::Oll.key("OLL skip") {
+        Some("it doesn't orient the last layer")
+    } else if step == Step::Coll && !turned.iter().any(corners_solved) {
+        Some("it doesn't solve the corners of the last layer")
+    } else if matches!(step, Step::Pll | Step::Zbll) && !turned.iter().any(|cube| cube.is_solved()) {
+        Some("it doesn't solve the last layer")
+    } else {
+        None
+    }
+}
+
+pub(crate) fn verify_last_layer(step: Step, data: &HashMap<Vec<usize>, Algorithm>) -> Report {
+    let set = *DataSet::ALL.iter().find(|set| set.step() == Some(step)).unwrap();
+    let mut findings = vec![];
+    for key in step.keys() {
+        let name = step.name(&key).to_string();
+        let finding = |algorithm: &Algorithm, problem| Finding {
+            set,
+            case: name.clone(),
+            solved: vec![],
+            algorithm: algorithm.clone(),
+            problem,
+        };
+        let algorithm = match data.get(&key) {
+            Some(algorithm) => algorithm,
+            None => {
+                findings.push(finding(&Algorithm::from(""), Problem::Missing));
+                continue;
+            }
+        };
+
+        let wrong = |algorithm: &Algorithm| last_layer_problem(step, &key, algorithm);
+        if let Some(what) = wrong(algorithm) {
+            findings.push(finding(algorithm, Problem::Broken(what.to_string())));
+        } else if let Some(shorter) = shorter(algorithm, |a| wrong(a).is_none()) {
+            findings.push(finding(algorithm, Problem::Suboptimal(shorter)));
+        }
+    }
+    Report { entries: data.len(), findings }
+}
+
+// the report of the data in use
+#[allow(dead_code)]
+pub fn verify(set: DataSet) -> Report {
+    match set {
+        DataSet::Cross => verify_cross(cross_data()),
+        DataSet::F2l => verify_f2l(f2l_data()),
+        _ => {
+            let step = set.step().unwrap();
+            verify_last_layer(step, step.data())
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn verify_all() -> Report {
+    DataSet::ALL.iter().fold(Report::default(), |report, set| report.merge(verify(*set)))
+}
@@ -4,6 +4,7 @@ use std::iter::FromIterator;
 use super::piece::face::Face;
 use serde::{Deserialize, Serialize, Serializer, Deserializer};
 use serde::de::{self, Visitor};
+use std::ops::Add;
 
 #[macro_export]
 macro_rules! alg {
@@ -12,27 +13,55 @@ macro_rules! alg {
     };
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-enum MoveType {
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MoveType {
     L, R, U, D, F, B
 }
 
 impl From<char> for MoveType {
     fn from(c: char) -> Self {
-        match c {
-            'L' => MoveType::L,
-            'R' => MoveType::R,
-            'U' => MoveType::U,
-            'D' => MoveType::D,
-            'F' => MoveType::F,
-            'B' => MoveType::B,
-            _ => panic!("invalid move type '{}'", c),
-        }
+        MoveType::parse(c).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl From<Face> for MoveType {
+    fn from(face: Face) -> Self {
+        *MoveType::ALL
+            .iter()
+            .find(|t| t.face() == face)
+            .unwrap_or_else(|| panic!("invalid face {:?}", face))
     }
 }
 
 impl MoveType {
-    fn base_move(&self) -> Self {
+    pub const ALL: [MoveType; 6] = [
+        MoveType::L, MoveType::R, MoveType::U, MoveType::D, MoveType::F, MoveType::B
+    ];
+
+    pub fn parse(c: char) -> Result<Self, String> {
+        match c {
+            'L' => Ok(MoveType::L),
+            'R' => Ok(MoveType::R),
+            'U' => Ok(MoveType::U),
+            'D' => Ok(MoveType::D),
+            'F' => Ok(MoveType::F),
+            'B' => Ok(MoveType::B),
+            _ => Err(format!("invalid move type '{}'", c)),
+        }
+    }
+
+    pub fn face(&self) -> Face {
+        match self {
+            MoveType::U => 0,
+            MoveType::L => 5,
+            MoveType::F => 1,
+            MoveType::R => 2,
+            MoveType::B => 4,
+            MoveType::D => 3,
+        }.into()
+    }
+
+    pub(crate) fn base_move(&self) -> Self {
         match self {
             MoveType::L | MoveType::R => MoveType::L,
             MoveType::U | MoveType::D => MoveType::D,
@@ -57,24 +86,40 @@ pub struct Move(MoveType, u8);
 
 impl From<&str> for Move {
     fn from(s: &str) -> Self {
+        Move::parse(s).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Move {
+    pub fn parse(s: &str) -> Result<Self, String> {
         let mut chars = s.chars();
 
-        let move_type = MoveType::from(
-            chars.next().unwrap_or_else(|| panic!("move must have a length of at least 1"))
-        );
+        let move_type = MoveType::parse(
+            chars.next().ok_or_else(|| "move must have a length of at least 1".to_string())?
+        )?;
 
         let quantifier = match chars.next() {
             None => 1,
             Some('2') => 2,
             Some('\'') => 3,
-            Some(c) => panic!("invalid character '{}' as move quantifier", c),
+            Some(c) => return Err(format!("invalid character '{}' as move quantifier", c)),
         };
+        if let Some(c) = chars.next() {
+            return Err(format!("unexpected character '{}' in move '{}'", c, s));
+        }
 
-        Self(move_type, quantifier)
+        Ok(Self(move_type, quantifier))
+    }
+
+    pub fn new(move_type: MoveType, times: u8) -> Self {
+        assert!((1..=3).contains(&times), "illegal move quantifier: {}", times);
+        Self(move_type, times)
+    }
+
+    pub fn move_type(&self) -> MoveType {
+        self.0
     }
-}
 
-impl Move {
     fn reversed(&self) -> Self {
         Self(self.0, match self.1 {
             1 => 3,
@@ -84,20 +129,13 @@ impl Move {
         })
     }
 
-    fn base_move(&self) -> MoveType {
+    pub(crate) fn base_move(&self) -> MoveType {
         self.0.base_move()
     }
 
     // TODO: maybe implement some cube state where 'R' isn't always the same face
     pub fn face(&self) -> Face {
-        match self.0 {
-            MoveType::U => 0,
-            MoveType::L => 5,
-            MoveType::F => 1,
-            MoveType::R => 2,
-            MoveType::B => 4,
-            MoveType::D => 3,
-        }.into()
+        self.0.face()
     }
 
     pub fn times(&self) -> u8 {
@@ -122,9 +160,17 @@ impl Debug for Move {
     }
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct Algorithm(Vec<Move>);
 
+// how moves are counted: half turn metric (every move is 1) or quarter turn metric
+// (half turns are 2)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Metric {
+    Htm,
+    Qtm,
+}
+
 impl<S: AsRef<str>> From<S> for Algorithm {
     fn from(s: S) -> Self {
         Self(s.as_ref().split_whitespace().map(|s| Move::from(s)).collect())
@@ -152,6 +198,25 @@ impl FromIterator<Move> for Algorithm {
 
 #[allow(dead_code)]
 impl Algorithm {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        s.split_whitespace().map(Move::parse).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn length(&self, metric: Metric) -> usize {
+        match metric {
+            Metric::Htm => self.len(),
+            Metric::Qtm => self.0.iter().map(|m| if m.1 == 2 { 2 } else { 1 }).sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn reversed(&self) -> Self {
         let reversed_iter = self.0
             .iter()
@@ -214,6 +279,16 @@ impl Algorithm {
     }
 }
 
+// concatenation
+impl Add for Algorithm {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self.0.extend(other.0);
+        self
+    }
+}
+
 impl IntoIterator for Algorithm {
     type Item = Move;
     type IntoIter = <Vec<Move> as IntoIterator>::IntoIter;
@@ -245,24 +320,23 @@ impl Serialize for Algorithm {
     }
 }
 
-struct StrVisitor;
+struct AlgorithmVisitor;
 
-impl<'de> Visitor<'de> for StrVisitor {
-    type Value = String;
+impl<'de> Visitor<'de> for AlgorithmVisitor {
+    type Value = Algorithm;
 
     fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
         write!(formatter, "a valid algorithm")
     }
 
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: de::Error {
-        Ok(value.to_string())
+        Algorithm::parse(value).map_err(E::custom)
     }
 }
 
 impl<'de> Deserialize<'de> for Algorithm {
     fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error> where
         D: Deserializer<'de> {
-        deserializer.deserialize_str(StrVisitor).map(|s|
-            Algorithm::from(s.as_str()))
+        deserializer.deserialize_str(AlgorithmVisitor)
     }
 }
\ No newline at end of file
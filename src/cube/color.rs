@@ -1,64 +1,141 @@
 use crate::cube::Cube;
-use std::fmt::{Debug, Formatter, Error, Display};
-use colored::{Colorize, ColoredString};
-use itertools::Itertools;
-
-fn to_color(f: u8) -> ColoredString {
-    let s = "  ";
-    match f {
-        0 => s.on_white(),
-        1 => s.on_red(),
-        2 => s.on_blue(),
-        4 => s.on_magenta(),
-        5 => s.on_green(),
-        3 => s.on_yellow(),
-        _ => unreachable!()
-    }
+use crate::cube::piece::face::Face;
+use crate::cube::symmetry::Symmetry;
+use colored::{Color, Colorize};
+use colored::control::SHOULD_COLORIZE;
+
+// the color of a sticker: a letter for text, its rgb value for svg and truecolor
+// terminals and the closest of the 16 ansi colors for the others
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct StickerColor {
+    pub letter: char,
+    pub rgb: [u8; 3],
+    pub ansi: Color,
 }
 
+// the squares of the unicode top views and roughly the colors they're drawn in
+const EMOJIS: [(&str, [u8; 3]); 7] = [
+    ("⬜", [255, 255, 255]),
+    ("🟥", [220, 30, 50]),
+    ("🟦", [0, 80, 200]),
+    ("🟨", [255, 220, 0]),
+    ("🟧", [255, 120, 0]),
+    ("🟩", [0, 160, 80]),
+    ("⬛", [70, 70, 70]),
+];
+
+#[allow(dead_code)]
+impl StickerColor {
+    pub const WHITE: Self = Self::new('W', [0xff, 0xff, 0xff], Color::White);
+    pub const RED: Self = Self::new('R', [0xc4, 0x1e, 0x3a], Color::Red);
+    pub const BLUE: Self = Self::new('B', [0x00, 0x51, 0xba], Color::Blue);
+    pub const YELLOW: Self = Self::new('Y', [0xff, 0xd5, 0x00], Color::Yellow);
+    // there is no orange among the ansi colors
+    pub const ORANGE: Self = Self::new('O', [0xff, 0x58, 0x00], Color::Magenta);
+    pub const GREEN: Self = Self::new('G', [0x00, 0x9e, 0x60], Color::Green);
+    pub const GREY: Self = Self::new('-', [0x80, 0x80, 0x80], Color::BrightBlack);
+
+    pub const fn new(letter: char, rgb: [u8; 3], ansi: Color) -> Self {
+        Self { letter, rgb, ansi }
+    }
+
+    // "#rrggbb"
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.rgb[0], self.rgb[1], self.rgb[2])
+    }
+
+    // the closest colored square
+    pub fn emoji(&self) -> &'static str {
+        let distance = |rgb: &[u8; 3]| (0..3)
+            .map(|i| (self.rgb[i] as i32 - rgb[i] as i32).pow(2))
+            .sum::<i32>();
+        EMOJIS.iter().min_by_key(|(_, rgb)| distance(rgb)).unwrap().0
+    }
+}
 
-// TODO: maybe find a better way to do this
-#[derive(Copy, Clone)]
-pub struct ColoredCube<'a>(&'a Cube);
-
-fn color_cube(uncolored: String) -> String {
-    // TODO: fix ugly replaces
-    uncolored
-        .replace("\n\n", "nn")
-        .replace("\n", "\n\n")
-        .replace("nn", "\n\n\n")
-        .replace(" ", "   ")
-        .replace("     ", "    ")
-        .chars()
-        .map(|c| {
-            if c.is_numeric() {
-                to_color(c.to_string()
-                    .parse()
-                    .expect(&format!("couldn't parse '{}' as u8", c))
-                )
-            } else {
-                c.to_string().normal()
-            }
-        })
-        .join("")
+// the colors of faces 0 to 5 and of unknown stickers
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ColorScheme {
+    pub faces: [StickerColor; 6],
+    pub unknown: StickerColor,
+    // 24-bit colors in the terminal instead of the ansi ones
+    pub truecolor: bool,
 }
 
-impl<'a> Display for ColoredCube<'a> {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        write!(f, "{}", color_cube(format!("{:?}", self.0)))
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::western()
     }
 }
 
-// just for convenience, TODO: remove
-impl<'a> Debug for ColoredCube<'a> {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        Display::fmt(self, f)
+#[allow(dead_code)]
+impl ColorScheme {
+    // white opposite yellow, red opposite orange and blue opposite green
+    pub fn western() -> Self {
+        use StickerColor as C;
+        Self::custom([C::WHITE, C::RED, C::BLUE, C::YELLOW, C::ORANGE, C::GREEN])
+    }
+
+    // white opposite blue and yellow opposite green
+    pub fn japanese() -> Self {
+        use StickerColor as C;
+        Self::custom([C::WHITE, C::RED, C::YELLOW, C::BLUE, C::ORANGE, C::GREEN])
+    }
+
+    pub fn custom(faces: [StickerColor; 6]) -> Self {
+        Self { faces, unknown: StickerColor::GREY, truecolor: false }
+    }
+
+    pub fn with_truecolor(self, truecolor: bool) -> Self {
+        Self { truecolor, ..self }
+    }
+
+    // the unknown color for None
+    pub fn color(&self, face: Option<Face>) -> StickerColor {
+        face.map_or(self.unknown, |f| self.faces[f.index()])
+    }
+
+    // the face of a sticker written as a letter of the scheme
+    pub fn face_of(&self, letter: char) -> Option<Face> {
+        self.faces.iter()
+            .position(|c| c.letter == letter)
+            .map(|i| Face::new(i as u8))
+    }
+
+    // s on the background color of a face (the unknown color for None)
+    pub fn paint(&self, s: &str, face: Option<Face>) -> String {
+        let color = self.color(face);
+        if !self.truecolor {
+            s.on_color(color.ansi).to_string()
+        } else if SHOULD_COLORIZE.should_colorize() {
+            let [r, g, b] = color.rgb;
+            format!("\x1b[48;2;{};{};{}m{}\x1b[0m", r, g, b, s)
+        } else {
+            s.to_string()
+        }
     }
 }
 
+// a cube with the colors to show it with
+#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Recolored {
+    pub cube: Cube,
+    pub scheme: ColorScheme,
+}
+
 #[allow(dead_code)]
 impl Cube {
-    pub fn colored(&self) -> ColoredCube {
-        ColoredCube(self)
+    // the state recorded with one scheme shown with another: when the colors of to are
+    // those of from turned, the cube is turned as a whole so that every sticker keeps its
+    // color, otherwise (as between western and japanese) every face takes the color to
+    // gives it and the cube stays as it is
+    pub fn recolored(&self, from: &ColorScheme, to: &ColorScheme) -> Recolored {
+        let image = |f: usize| to.faces.iter().position(|c| *c == from.faces[f]).map(|i| Face::new(i as u8));
+        let rotation = Symmetry::all()
+            .iter()
+            .find(|s| !s.is_reflection() && (0..6).all(|f| Some(s.image(Face::new(f as u8))) == image(f)));
+        let cube = rotation.map_or(*self, |rotation| self.symmetric(rotation));
+        Recolored { cube, scheme: *to }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,139 @@
+use crate::cube::Cube;
+use crate::cube::slot::FIRST_SLICE_EDGE;
+
+pub const CORNER_ORIENTATIONS: u16 = 2187; // 3^7
+pub const EDGE_ORIENTATIONS: u16 = 2048; // 2^11
+pub const CORNER_PERMUTATIONS: u16 = 40320; // 8!
+pub const EDGE_PERMUTATIONS: u32 = 479_001_600; // 12!
+pub const UD_SLICES: u16 = 495; // 12 choose 4
+
+fn factorial(n: u32) -> u32 {
+    (1..=n).product()
+}
+
+fn binomial(n: u32, k: u32) -> u32 {
+    if k > n { return 0 }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+// Lehmer code of a permutation of 0..n
+pub(crate) fn permutation_index(permutation: &[u8]) -> u32 {
+    let n = permutation.len() as u32;
+    permutation
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let smaller = permutation[i + 1..].iter().filter(|q| *q < p).count() as u32;
+            smaller * factorial(n - 1 - i as u32)
+        })
+        .sum()
+}
+
+pub(crate) fn permutation_from_index(mut index: u32, permutation: &mut [u8]) {
+    let n = permutation.len() as u32;
+    let mut remaining = (0..n as u8).collect::<Vec<_>>();
+    for (i, p) in permutation.iter_mut().enumerate() {
+        let weight = factorial(n - 1 - i as u32);
+        *p = remaining.remove((index / weight) as usize);
+        index %= weight;
+    }
+}
+
+// coordinates, each with a constructor for the cube which has that coordinate and
+// is otherwise solved
+#[allow(dead_code)]
+impl Cube {
+    pub fn corner_orientation(&self) -> u16 {
+        self.co[..7].iter().fold(0, |acc, o| acc * 3 + *o as u16)
+    }
+
+    pub fn from_corner_orientation(mut coordinate: u16) -> Self {
+        assert!(coordinate < CORNER_ORIENTATIONS, "invalid corner orientation {}", coordinate);
+        let mut cube = Self::solved();
+        for o in cube.co[..7].iter_mut().rev() {
+            *o = (coordinate % 3) as u8;
+            coordinate /= 3;
+        }
+        // the total twist is always a multiple of 3
+        let sum: u8 = cube.co[..7].iter().sum();
+        cube.co[7] = (3 - sum % 3) % 3;
+        cube
+    }
+
+    pub fn edge_orientation(&self) -> u16 {
+        self.eo[..11].iter().fold(0, |acc, o| acc * 2 + *o as u16)
+    }
+
+    pub fn from_edge_orientation(mut coordinate: u16) -> Self {
+        assert!(coordinate < EDGE_ORIENTATIONS, "invalid edge orientation {}", coordinate);
+        let mut cube = Self::solved();
+        for o in cube.eo[..11].iter_mut().rev() {
+            *o = (coordinate % 2) as u8;
+            coordinate /= 2;
+        }
+        // the number of flipped edges is always even
+        let sum: u8 = cube.eo[..11].iter().sum();
+        cube.eo[11] = sum % 2;
+        cube
+    }
+
+    pub fn corner_permutation(&self) -> u16 {
+        permutation_index(&self.cp) as u16
+    }
+
+    // note: odd permutations can't be reached without also swapping two edges
+    pub fn from_corner_permutation(coordinate: u16) -> Self {
+        assert!(coordinate < CORNER_PERMUTATIONS, "invalid corner permutation {}", coordinate);
+        let mut cube = Self::solved();
+        permutation_from_index(coordinate as u32, &mut cube.cp);
+        cube
+    }
+
+    pub fn edge_permutation(&self) -> u32 {
+        permutation_index(&self.ep)
+    }
+
+    // note: odd permutations can't be reached without also swapping two corners
+    pub fn from_edge_permutation(coordinate: u32) -> Self {
+        assert!(coordinate < EDGE_PERMUTATIONS, "invalid edge permutation {}", coordinate);
+        let mut cube = Self::solved();
+        permutation_from_index(coordinate, &mut cube.ep);
+        cube
+    }
+
+    // which 4 slots hold the E-slice edges (regardless of their order)
+    pub fn ud_slice(&self) -> u16 {
+        self.ep
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| **p >= FIRST_SLICE_EDGE)
+            .enumerate()
+            .map(|(k, (slot, _))| binomial(slot as u32, k as u32 + 1))
+            .sum::<u32>() as u16
+    }
+
+    pub fn from_ud_slice(coordinate: u16) -> Self {
+        assert!(coordinate < UD_SLICES, "invalid ud slice {}", coordinate);
+
+        let mut occupied = [false; 12];
+        let mut remaining = coordinate as u32;
+        for k in (1..=4).rev() {
+            let slot = (0..12).rev().find(|s| binomial(*s, k) <= remaining).unwrap();
+            remaining -= binomial(slot, k);
+            occupied[slot as usize] = true;
+        }
+
+        let mut cube = Self::solved();
+        let (mut slice, mut other) = (FIRST_SLICE_EDGE, 0);
+        for (slot, p) in cube.ep.iter_mut().enumerate() {
+            if occupied[slot] {
+                *p = slice;
+                slice += 1;
+            } else {
+                *p = other;
+                other += 1;
+            }
+        }
+        cube
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cube::Cube;
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::{CornerPosition, EdgePosition};
+use crate::cube::transpose::Transposed;
+use crate::algorithm_data::CEPosition;
+
+// f2l.txt is written for the slot of the corner (0 4 5) and the edge (4 5), every
+// slot is turned into that one around the 0-3 axis, front 1 being the identity
+pub(crate) const FRONTS: [u8; 4] = [1, 2, 4, 5];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SlotStatus {
+    Solved,
+    // where the corner and the edge of the slot are after normalization, this is
+    // a key of f2l.txt
+    Unsolved(CEPosition),
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug)]
+pub struct F2lSlot {
+    pub corner: CornerPosition,
+    pub edge: EdgePosition,
+    // transposing from cpos!(front, 3) to cpos!(1, 3) normalizes the slot
+    pub front: Face,
+    pub status: SlotStatus,
+}
+
+#[allow(dead_code)]
+impl F2lSlot {
+    pub fn is_solved(&self) -> bool {
+        self.status == SlotStatus::Solved
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // the four slots around face 0 (the cross face)
+    pub fn f2l_slots(&self) -> Vec<F2lSlot> {
+        FRONTS.iter().map(|front| self.f2l_slot(Face::new(*front))).collect()
+    }
+
+    pub fn f2l_slot(&self, front: Face) -> F2lSlot {
+        let (default_corner, default_edge) = (pos!(0, 4, 5), pos!(4, 5));
+        let normalized = self.transposed(cpos!(front, 3), cpos!(1, 3));
+
+        let corner = normalized.iter_corners().find(|c| c.id == default_corner).unwrap().pos;
+        let edge = normalized.iter_edges().find(|e| e.id == default_edge).unwrap().pos;
+        let status = if corner == default_corner && edge == default_edge {
+            SlotStatus::Solved
+        } else {
+            SlotStatus::Unsolved((corner, edge))
+        };
+
+        F2lSlot {
+            corner: default_corner.transposed(cpos!(1, 3), cpos!(front, 3)).sorted(),
+            edge: default_edge.transposed(cpos!(1, 3), cpos!(front, 3)).sorted(),
+            front,
+            status,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::cube::Cube;
+use crate::cube::algorithm::Algorithm;
+use std::ops::Mul;
+
+// a * b is the cube state reached by applying a, then b (starting from solved)
+impl Mul for Cube {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut product = self;
+
+        for slot in 0..8 {
+            let from = other.cp[slot] as usize;
+            product.cp[slot] = self.cp[from];
+            product.co[slot] = (self.co[from] + other.co[slot]) % 3;
+        }
+
+        for slot in 0..12 {
+            let from = other.ep[slot] as usize;
+            product.ep[slot] = self.ep[from];
+            product.eo[slot] = (self.eo[from] + other.eo[slot]) % 2;
+        }
+
+        // untracked centers count as solved
+        if let Some(centers) = other.centers {
+            let mut sum = self.centers.unwrap_or([0; 6]);
+            (0..6).for_each(|f| sum[f] = (sum[f] + centers[f]) % 4);
+            product.centers = Some(sum);
+        }
+
+        product
+    }
+}
+
+// group operations
+#[allow(dead_code)]
+impl Cube {
+    pub fn from_algorithm(algorithm: &Algorithm) -> Self {
+        let mut cube = Self::solved();
+        cube.apply(algorithm);
+        cube
+    }
+
+    // inverse * self == self * inverse == solved
+    pub fn inverse(&self) -> Self {
+        let mut inverse = *self;
+
+        for slot in 0..8 {
+            let piece = self.cp[slot] as usize;
+            inverse.cp[piece] = slot as u8;
+            inverse.co[piece] = (3 - self.co[slot]) % 3;
+        }
+
+        for slot in 0..12 {
+            let piece = self.ep[slot] as usize;
+            inverse.ep[piece] = slot as u8;
+            inverse.eo[piece] = self.eo[slot];
+        }
+
+        inverse.centers = self.centers.map(|c| c.map(|t| (4 - t) % 4));
+
+        inverse
+    }
+    // orientations which add up and permutations of the same parity, as for every
+    // state reached by moves
+    pub fn is_solvable(&self) -> bool {
+        let parity = |p: &[u8]| (0..p.len())
+            .map(|i| (i + 1..p.len()).filter(|j| p[i] > p[*j]).count())
+            .sum::<usize>() % 2;
+        self.co.iter().map(|o| *o as usize).sum::<usize>() % 3 == 0
+            && self.eo.iter().map(|o| *o as usize).sum::<usize>() % 2 == 0
+            && parity(&self.cp) == parity(&self.ep)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::cube::{Cube, FaceMatrix, surrounding};
+use crate::cube::color::ColorScheme;
+use crate::cube::piece::position::CubePosition;
+use crate::cube::svg::SvgOptions;
+use std::fmt::{Display, Formatter, Error, Write};
+
+const COS_30: f64 = 0.866_025_403_784_438_6;
+
+// the cube seen from above, in front and to the right of the front of a position:
+// the face above it, its front and the face to the right are visible
+pub struct IsometricView<'a> {
+    cube: &'a Cube,
+    position: CubePosition,
+    // for Display
+    scheme: ColorScheme,
+}
+
+#[allow(dead_code)]
+impl<'a> IsometricView<'a> {
+    pub fn with_scheme(self, scheme: ColorScheme) -> Self {
+        Self { scheme, ..self }
+    }
+
+    // up (with the front below), front and right (with down below)
+    fn faces(&self) -> [FaceMatrix; 3] {
+        let CubePosition { front, down } = self.position;
+        let [_, _, up, right] = surrounding(self.position);
+        let face = |position| FaceMatrix::from(|p| self.cube.sticker(p), position);
+        [face(cpos!(up, front)), face(self.position), face(cpos!(right, down))]
+    }
+
+    // stickers are polygons of the isometric projection of the visible faces
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let size = options.sticker_size as f64;
+        let margin = size / 4.;
+        let (width, height) = (6. * COS_30 * size + 2. * margin, 6. * size + 2. * margin);
+
+        // x to the right, y up and z to the front, the cube is [0, 3]³
+        let project = |[x, y, z]: [f64; 3]| (
+            margin + (3. + x - z) * COS_30 * size,
+            margin + (3. - y + (x + z) / 2.) * size,
+        );
+        // the corners of the sticker at row, column of a face
+        let corners: [&dyn Fn(f64, f64) -> [[f64; 3]; 4]; 3] = [
+            &|i, j| [[j, 3., i], [j + 1., 3., i], [j + 1., 3., i + 1.], [j, 3., i + 1.]],
+            &|i, j| [[j, 3. - i, 3.], [j + 1., 3. - i, 3.], [j + 1., 2. - i, 3.], [j, 2. - i, 3.]],
+            &|i, j| [[3., 3. - i, 3. - j], [3., 3. - i, 2. - j], [3., 2. - i, 2. - j], [3., 2. - i, 3. - j]],
+        ];
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0:.0}\" height=\"{1:.0}\" viewBox=\"0 0 {0:.0} {1:.0}\">\n",
+            width, height,
+        );
+        for (face, corners) in self.faces().iter().zip(corners.iter()) {
+            for (i, line) in face.0.iter().enumerate() {
+                for (j, sticker) in line.iter().enumerate() {
+                    let points = corners(i as f64, j as f64).iter()
+                        .map(|c| {
+                            let (x, y) = project(*c);
+                            format!("{:.1},{:.1}", x, y)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    writeln!(
+                        svg,
+                        r##"<polygon points="{}" fill="{}" stroke="#000000" stroke-width="1"/>"##,
+                        points, options.scheme.color(Some(*sticker)).hex(),
+                    ).unwrap();
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// up is a parallelogram leaning to the right above front, right is narrower and
+// rises to the right next to it
+//    UUUUUU
+//   UUUUUUR
+//  UUUUUURR
+// FFFFFFRRR
+// FFFFFFRR
+// FFFFFFR
+impl Display for IsometricView<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let [up, front, right] = self.faces();
+        for line in 0..6 {
+            write!(f, "{}", " ".repeat(3usize.saturating_sub(line)))?;
+            let row = if line < 3 { &up.0[line] } else { &front.0[line - 3] };
+            for sticker in row {
+                write!(f, "{}", self.scheme.paint("  ", Some(*sticker)))?;
+            }
+            // column j of right rises one line per column
+            for j in 0..3 {
+                if let Some(row) = (line + j).checked_sub(3).filter(|i| *i < 3) {
+                    write!(f, "{}", self.scheme.paint(" ", Some(right.0[row][j])))?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // cpos!(1, 3) shows faces 0, 1 and 2
+    pub fn isometric(&self, position: CubePosition) -> IsometricView<'_> {
+        IsometricView { cube: self, position, scheme: ColorScheme::default() }
+    }
+}
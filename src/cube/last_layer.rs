@@ -0,0 +1,618 @@
+use crate::cube::{Cube, Edge};
+use crate::cube::algorithm::{Algorithm, Move, MoveType};
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::EdgePosition;
+use crate::cube::symmetry::Symmetry;
+use crate::cube::transpose::Transposed;
+use crate::cube::f2l::FRONTS;
+use crate::algorithm_data::{oll_data, pll_data, coll_data, zbll_data, wv_data, vls_data};
+use std::collections::{HashMap, HashSet};
+use itertools::Itertools;
+
+// the last layer positions in the order used by the keys of oll.txt and pll.txt,
+// the faces of every position in ascending order
+const POSITIONS: [&[u8]; 8] = [
+    &[1, 3], &[1, 2, 3], &[2, 3], &[2, 3, 4],
+    &[3, 4], &[3, 4, 5], &[3, 5], &[1, 3, 5],
+];
+
+pub(crate) const LAST_LAYER: Face = Face::new(3);
+
+// the slots of the last layer pieces, the same for corners and edges (see slot.rs)
+const LAST_LAYER_SLOTS: std::ops::Range<u8> = 4..8;
+
+lazy_static! {
+    // the data is written with the last layer on top, the last layer of the cube is
+    // face 3 and the front of the data is face 5
+    pub(crate) static ref DATA_FRAME: Symmetry = Symmetry::rotation_to(LAST_LAYER, Face::new(5));
+}
+
+// an oll key is the list of (last layer colored) stickers facing the last layer,
+// stickers are numbered position by position
+const OLL_NAMES: [(&[usize], &str); 58] = [
+    (&[1, 4, 6, 8, 10, 12, 15, 18], "OLL skip"),
+    (&[0, 2, 5, 9, 11, 13, 16, 17], "OLL 1"),
+    (&[0, 2, 5, 7, 11, 14, 16, 17], "OLL 2"),
+    (&[0, 3, 5, 9, 11, 12, 16, 17], "OLL 3"),
+    (&[0, 2, 5, 8, 11, 13, 16, 19], "OLL 4"),
+    (&[0, 3, 5, 9, 10, 12, 15, 17], "OLL 5"),
+    (&[0, 2, 6, 8, 10, 13, 16, 19], "OLL 6"),
+    (&[1, 3, 6, 9, 11, 14, 16, 18], "OLL 7"),
+    (&[1, 4, 5, 7, 11, 13, 15, 19], "OLL 8"),
+    (&[1, 2, 6, 7, 11, 12, 16, 19], "OLL 9"),
+    (&[1, 3, 5, 8, 11, 14, 15, 17], "OLL 10"),
+    (&[1, 4, 5, 9, 11, 14, 15, 17], "OLL 11"),
+    (&[0, 2, 5, 8, 10, 13, 15, 19], "OLL 12"),
+    (&[1, 3, 5, 9, 10, 14, 16, 18], "OLL 13"),
+    (&[1, 2, 5, 7, 10, 12, 16, 19], "OLL 14"),
+    (&[1, 4, 5, 9, 10, 14, 16, 17], "OLL 15"),
+    (&[1, 2, 5, 8, 10, 13, 16, 19], "OLL 16"),
+    (&[0, 4, 5, 7, 11, 12, 16, 17], "OLL 17"),
+    (&[0, 3, 5, 7, 11, 12, 16, 18], "OLL 18"),
+    (&[0, 4, 5, 8, 11, 13, 16, 17], "OLL 19"),
+    (&[0, 4, 5, 8, 11, 12, 16, 18], "OLL 20"),
+    (&[1, 3, 6, 7, 10, 14, 15, 19], "OLL 21"),
+    (&[1, 2, 6, 7, 10, 14, 15, 17], "OLL 22"),
+    (&[1, 4, 6, 8, 10, 14, 15, 19], "OLL 23"),
+    (&[1, 3, 6, 8, 10, 12, 15, 19], "OLL 24"),
+    (&[1, 2, 6, 8, 10, 14, 15, 18], "OLL 25"),
+    (&[1, 2, 6, 8, 10, 13, 15, 19], "OLL 26"),
+    (&[1, 3, 6, 9, 10, 14, 15, 18], "OLL 27"),
+    (&[0, 4, 5, 8, 10, 12, 15, 18], "OLL 28"),
+    (&[1, 3, 6, 8, 11, 12, 16, 19], "OLL 29"),
+    (&[0, 4, 5, 8, 10, 13, 15, 17], "OLL 30"),
+    (&[0, 3, 6, 8, 10, 12, 16, 19], "OLL 31"),
+    (&[0, 3, 5, 8, 10, 12, 15, 19], "OLL 32"),
+    (&[1, 3, 5, 8, 10, 12, 16, 19], "OLL 33"),
+    (&[1, 2, 5, 9, 10, 12, 16, 18], "OLL 34"),
+    (&[0, 4, 5, 9, 10, 12, 15, 19], "OLL 35"),
+    (&[0, 4, 6, 7, 10, 12, 16, 17], "OLL 36"),
+    (&[1, 4, 6, 9, 11, 12, 16, 19], "OLL 37"),
+    (&[1, 3, 6, 8, 11, 13, 16, 18], "OLL 38"),
+    (&[1, 2, 5, 8, 10, 14, 16, 18], "OLL 39"),
+    (&[1, 4, 5, 7, 10, 12, 16, 17], "OLL 40"),
+    (&[1, 3, 6, 7, 11, 12, 16, 18], "OLL 41"),
+    (&[1, 2, 6, 8, 11, 12, 16, 17], "OLL 42"),
+    (&[1, 4, 5, 9, 11, 13, 15, 18], "OLL 43"),
+    (&[0, 2, 5, 8, 10, 12, 15, 17], "OLL 44"),
+    (&[1, 2, 5, 8, 10, 12, 16, 17], "OLL 45"),
+    (&[0, 4, 6, 9, 11, 13, 15, 18], "OLL 46"),
+    (&[0, 3, 6, 9, 10, 13, 16, 19], "OLL 47"),
+    (&[1, 2, 6, 7, 11, 14, 16, 17], "OLL 48"),
+    (&[0, 2, 6, 7, 10, 14, 16, 17], "OLL 49"),
+    (&[1, 3, 6, 9, 11, 13, 16, 19], "OLL 50"),
+    (&[1, 2, 5, 7, 10, 14, 16, 17], "OLL 51"),
+    (&[0, 3, 6, 9, 11, 13, 15, 19], "OLL 52"),
+    (&[1, 2, 6, 9, 11, 13, 16, 17], "OLL 53"),
+    (&[0, 2, 6, 9, 10, 13, 16, 17], "OLL 54"),
+    (&[0, 2, 6, 9, 11, 13, 15, 17], "OLL 55"),
+    (&[1, 2, 5, 9, 10, 13, 16, 17], "OLL 56"),
+    (&[1, 4, 5, 8, 10, 12, 16, 18], "OLL 57"),
+];
+
+// a pll key lists, for every last layer piece, the position it is currently at
+const PLL_NAMES: [(&[usize], &str); 22] = [
+    (&[0, 1, 2, 3, 4, 5, 6, 7], "PLL skip"),
+    (&[0, 5, 2, 1, 4, 3, 6, 7], "Aa-perm"),
+    (&[0, 1, 2, 5, 4, 7, 6, 3], "Ab-perm"),
+    (&[0, 7, 2, 5, 4, 3, 6, 1], "E-perm"),
+    (&[4, 1, 2, 3, 0, 7, 6, 5], "F-perm"),
+    (&[2, 7, 4, 1, 0, 5, 6, 3], "Ga-perm"),
+    (&[6, 5, 0, 3, 4, 7, 2, 1], "Gb-perm"),
+    (&[6, 5, 2, 3, 0, 7, 4, 1], "Gc-perm"),
+    (&[2, 7, 6, 1, 4, 5, 0, 3], "Gd-perm"),
+    (&[4, 1, 6, 3, 0, 5, 2, 7], "H-perm"),
+    (&[2, 3, 0, 1, 4, 5, 6, 7], "Ja-perm"),
+    (&[0, 1, 2, 5, 6, 3, 4, 7], "Jb-perm"),
+    (&[2, 7, 0, 5, 6, 3, 4, 1], "Na-perm"),
+    (&[0, 1, 6, 7, 4, 5, 2, 3], "Nb-perm"),
+    (&[6, 3, 2, 1, 4, 5, 0, 7], "Ra-perm"),
+    (&[0, 3, 2, 1, 6, 5, 4, 7], "Rb-perm"),
+    (&[4, 1, 2, 5, 0, 3, 6, 7], "T-perm"),
+    (&[4, 1, 2, 3, 6, 5, 0, 7], "Ua-perm"),
+    (&[6, 1, 2, 3, 0, 5, 4, 7], "Ub-perm"),
+    (&[0, 5, 4, 3, 2, 1, 6, 7], "V-perm"),
+    (&[2, 5, 0, 3, 4, 1, 6, 7], "Y-perm"),
+    (&[2, 1, 0, 3, 6, 5, 4, 7], "Z-perm"),
+];
+
+// the oll cases of the corners of an oriented last layer, which group the coll and zbll
+// cases by shape
+const SHAPES: [(&str, &str); 8] = [
+    ("OLL 21", "H"), ("OLL 22", "Pi"), ("OLL 23", "U"), ("OLL 24", "T"),
+    ("OLL 25", "L"), ("OLL 26", "Antisune"), ("OLL 27", "Sune"), ("OLL skip", "O"),
+];
+
+// winter variation and vls start with the pair of the slot DFR (of the data) in the last
+// layer, where this would insert it
+const LAST_SLOT_INSERT: &str = "R U R'";
+
+lazy_static! {
+    // the cubes of auf(0) to auf(3)
+    static ref AUFS: Vec<Cube> = (0..4).map(|t| Cube::from_algorithm(&auf(t))).collect();
+    static ref LAST_SLOT: Cube = Cube::from_algorithm(&Algorithm::from(LAST_SLOT_INSERT).symmetric(&DATA_FRAME));
+
+    // the cases of every step with their names, in the usual order
+    static ref OLL_CASES: Vec<(Vec<usize>, String)> = Step::Oll.named_cases();
+    static ref PLL_CASES: Vec<(Vec<usize>, String)> = Step::Pll.named_cases();
+    static ref COLL_CASES: Vec<(Vec<usize>, String)> = Step::Coll.named_cases();
+    static ref ZBLL_CASES: Vec<(Vec<usize>, String)> = Step::Zbll.named_cases();
+    static ref WV_CASES: Vec<(Vec<usize>, String)> = Step::Wv.named_cases();
+    static ref VLS_CASES: Vec<(Vec<usize>, String)> = Step::Vls.named_cases();
+}
+
+// whether the edges of an oll key are oriented
+fn edges_oriented(key: &[usize]) -> bool {
+    key.iter().step_by(2).eq(OLL_NAMES[0].0.iter().step_by(2))
+}
+
+// the pieces which aren't in the last layer
+pub(crate) fn first_two_layers_solved(cube: &Cube) -> bool {
+    cube.iter_edges().all(|e| e.pos.0 == LAST_LAYER || e.pos.1 == LAST_LAYER || e.id == e.pos)
+        && cube.iter_corners().all(|c| [c.pos.0, c.pos.1, c.pos.2].contains(&LAST_LAYER) || c.id == c.pos)
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Step {
+    Oll,
+    Pll,
+    // the corners of a last layer with oriented edges, the edges are left to pll
+    Coll,
+    // the whole last layer once its edges are oriented
+    Zbll,
+    // winter variation: the last pair is inserted and the corners oriented, the edges
+    // of the last layer being oriented
+    Wv,
+    // valk last slot: the last pair is inserted and the last layer oriented
+    Vls,
+}
+
+impl Step {
+    pub(crate) fn data(self) -> &'static HashMap<Vec<usize>, Algorithm> {
+        match self {
+            Step::Oll => oll_data(),
+            Step::Pll => pll_data(),
+            Step::Coll => coll_data(),
+            Step::Zbll => zbll_data(),
+            Step::Wv => wv_data(),
+            Step::Vls => vls_data(),
+        }
+    }
+
+    fn names(self) -> &'static [(Vec<usize>, String)] {
+        match self {
+            Step::Oll => &OLL_CASES,
+            Step::Pll => &PLL_CASES,
+            Step::Coll => &COLL_CASES,
+            Step::Zbll => &ZBLL_CASES,
+            Step::Wv => &WV_CASES,
+            Step::Vls => &VLS_CASES,
+        }
+    }
+
+    pub(crate) fn name(self, key: &[usize]) -> &'static str {
+        self.names().iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, name)| name.as_str())
+            .unwrap_or_else(|| panic!("no name for key {:?}", key))
+    }
+
+    // the key of the case with the name
+    pub(crate) fn key(self, name: &str) -> Option<Vec<usize>> {
+        self.names().iter()
+            .find(|(_, n)| *n == name)
+            .map(|(key, _)| key.to_vec())
+    }
+
+    // every case, in the usual order
+    pub(crate) fn keys(self) -> Vec<Vec<usize>> {
+        self.names().iter().map(|(key, _)| key.to_vec()).collect()
+    }
+
+    // where the case is in the usual order (that of the names)
+    pub(crate) fn order(self, key: &[usize]) -> usize {
+        self.names().iter().position(|(k, _)| *k == key).unwrap()
+    }
+
+    // the turns of the last layer before and after the algorithm which a case may need,
+    // the last slot is turned to the slot of the data instead
+    fn aufs(self) -> Vec<(u8, u8)> {
+        let (pre, post) = match self {
+            Step::Oll => (4, 1),
+            Step::Pll | Step::Coll | Step::Zbll => (4, 4),
+            Step::Wv | Step::Vls => (1, 1),
+        };
+        (0..pre).cartesian_product(0..post).collect()
+    }
+
+    // the cubes which are the same case as this one, with the last layer turned
+    pub(crate) fn variants(self, cube: &Cube) -> Vec<Cube> {
+        self.aufs().into_iter().map(|(pre, post)| turned(cube, pre, post)).collect()
+    }
+
+    // the key of a cube as it is, None unless it's a case of the step
+    pub(crate) fn case_key(self, cube: &Cube) -> Option<Vec<usize>> {
+        match self {
+            Step::Oll => cube.oll_key(),
+            Step::Pll => {
+                if cube.oll_key()? != OLL_NAMES[0].0 { return None }
+                cube.pll_key()
+            }
+            // the oll key, then where the corners (or all the pieces) are
+            Step::Coll | Step::Zbll => {
+                let mut key = cube.oll_key().filter(|key| edges_oriented(key))?;
+                let pll = cube.pll_key()?;
+                match self {
+                    Step::Coll => key.extend(pll.into_iter().skip(1).step_by(2)),
+                    _ => key.extend(pll),
+                }
+                Some(key)
+            }
+            // the oll key once the pair is inserted
+            Step::Wv | Step::Vls => {
+                let inserted = *cube * *LAST_SLOT;
+                if !first_two_layers_solved(&inserted) { return None }
+                inserted.oll_key().filter(|key| self == Step::Vls || edges_oriented(key))
+            }
+        }
+    }
+
+    // a cube of the case of a key with the rest of the cube solved
+    pub(crate) fn case(self, key: &[usize]) -> Cube {
+        match self {
+            Step::Oll => Cube::from_oll_key(key),
+            Step::Pll => Cube::from_pll_key(key),
+            Step::Coll => {
+                // two edges are swapped when the corners are oddly permuted
+                let corners = &key[8..];
+                let odd = corners.iter().tuple_combinations().filter(|(a, b)| a > b).count() % 2 == 1;
+                let mut pll = vec![0, corners[0], 2, corners[1], 4, corners[2], 6, corners[3]];
+                if odd { pll.swap(0, 2) }
+                Cube::from_pll_key(&pll).oriented(&key[..8])
+            }
+            Step::Zbll => Cube::from_pll_key(&key[8..]).oriented(&key[..8]),
+            Step::Wv | Step::Vls => Cube::from_oll_key(key) * LAST_SLOT.inverse(),
+        }
+    }
+
+    // every cube of the cases of the step
+    pub(crate) fn states(self) -> Vec<Cube> {
+        let edges_oriented = |cube: &Cube| cube.eo[LAST_LAYER_SLOTS.start as usize..].iter().take(4).all(|o| *o == 0);
+        let states = match self {
+            Step::Oll | Step::Wv | Step::Vls => Cube::last_layer_states(false, true),
+            Step::Pll => Cube::last_layer_states(true, false),
+            Step::Coll | Step::Zbll => Cube::last_layer_states(true, true),
+        };
+        let states = states.into_iter().filter(|cube| !matches!(self, Step::Coll | Step::Zbll | Step::Wv) || edges_oriented(cube));
+        match self {
+            Step::Wv | Step::Vls => states.map(|cube| cube * LAST_SLOT.inverse()).collect(),
+            _ => states.collect(),
+        }
+    }
+
+    // the states of the step grouped by case, the cubes of a group are the variants of
+    // each other
+    pub(crate) fn groups(self) -> Vec<Vec<Cube>> {
+        self.grouped(self.states())
+    }
+
+    // some states grouped as the step turns the last layer
+    pub(crate) fn grouped(self, states: Vec<Cube>) -> Vec<Vec<Cube>> {
+        let mut seen = HashSet::new();
+        let mut groups = vec![];
+        for state in states {
+            if seen.contains(&state.packed()) { continue }
+            let variants = self.variants(&state);
+            seen.extend(variants.iter().map(Cube::packed));
+            groups.push(variants);
+        }
+        groups
+    }
+
+    // the key of the named case of a cube, whether the data has an entry for it or not
+    pub(crate) fn named_key(self, cube: &Cube) -> Option<Vec<usize>> {
+        self.variants(cube).iter()
+            .filter_map(|variant| self.case_key(variant))
+            .find(|key| self.names().iter().any(|(k, _)| k == key))
+    }
+
+    // the smallest key of the case of some variants
+    fn case_of(self, variants: &[Cube]) -> Vec<usize> {
+        variants.iter()
+            .filter_map(|variant| self.case_key(variant))
+            .min()
+            .unwrap_or_else(|| panic!("not a case of {:?}:\n{:?}", self, variants[0]))
+    }
+
+    // oll and pll have the usual names, for the others the case which is solved by
+    // nothing (or by the insertion of the pair) is the skip and the others are
+    // numbered in the order of their keys, by the shape of their corners for coll and
+    // zbll
+    fn named_cases(self) -> Vec<(Vec<usize>, String)> {
+        let prefix = match self {
+            Step::Oll => return OLL_NAMES.iter().map(|(key, name)| (key.to_vec(), name.to_string())).collect(),
+            Step::Pll => return PLL_NAMES.iter().map(|(key, name)| (key.to_vec(), name.to_string())).collect(),
+            Step::Coll => "COLL",
+            Step::Zbll => "ZBLL",
+            Step::Wv => "WV",
+            Step::Vls => "VLS",
+        };
+        let skip = match self {
+            Step::Wv | Step::Vls => self.case_of(&self.variants(&LAST_SLOT.inverse())),
+            _ => self.case_of(&self.variants(&Cube::solved())),
+        };
+        let keys = self.groups().iter()
+            .map(|variants| self.case_of(variants))
+            .unique()
+            .filter(|key| *key != skip)
+            .sorted()
+            .collect::<Vec<_>>();
+
+        let mut names = vec![(skip, format!("{} skip", prefix))];
+        match self {
+            Step::Coll | Step::Zbll => {
+                let shapes = keys.iter().map(|key| shape(&self.case(key))).collect::<Vec<_>>();
+                for (_, shape_name) in SHAPES.iter() {
+                    let keys = keys.iter().zip(&shapes).filter(|(_, s)| *s == shape_name).map(|(key, _)| key);
+                    names.extend(keys.enumerate().map(|(i, key)| (key.clone(), format!("{} {} {}", prefix, shape_name, i + 1))));
+                }
+            }
+            _ => names.extend(keys.into_iter().enumerate().map(|(i, key)| (key, format!("{} {}", prefix, i + 1)))),
+        }
+        names
+    }
+}
+
+// the shape of the corners of a cube with oriented edges
+fn shape(cube: &Cube) -> &'static str {
+    (0..4)
+        .filter_map(|t| turned(cube, t, 0).oll_key())
+        .find_map(|key| {
+            let name = OLL_NAMES.iter().find(|(k, _)| *k == key.as_slice())?.1;
+            SHAPES.iter().find(|(oll, _)| *oll == name).map(|(_, shape)| *shape)
+        })
+        .unwrap_or_else(|| panic!("no corner shape for\n{:?}", cube))
+}
+
+// the case which the algorithm of the data solves, up to the last turn
+fn turned(cube: &Cube, pre_auf: u8, post_auf: u8) -> Cube {
+    AUFS[post_auf as usize] * *cube * AUFS[pre_auf as usize]
+}
+
+pub(crate) fn auf(times: u8) -> Algorithm {
+    (1..4).filter(|t| *t == times)
+        .map(|t| Move::new(MoveType::from(LAST_LAYER), t))
+        .collect()
+}
+
+// a recognized case of the last layer (or the last slot)
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct LastLayerCase {
+    pub key: Vec<usize>,
+    pub name: &'static str,
+    // quarter turns of the last layer before and after the algorithm of the case
+    pub pre_auf: u8,
+    pub post_auf: u8,
+    // the whole thing (including the aufs) as it is to be applied to the cube
+    pub algorithm: Algorithm,
+}
+
+impl LastLayerCase {
+    fn new(step: Step, key: Vec<usize>, alg: &Algorithm, pre_auf: u8, post_auf: u8) -> Self {
+        Self {
+            name: step.name(&key),
+            algorithm: auf(pre_auf) + alg.symmetric(&DATA_FRAME) + auf(post_auf),
+            key,
+            pre_auf,
+            post_auf,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // the case solved by an algorithm of oll.txt or pll.txt (as written in the data)
+    pub fn from_last_layer_algorithm(algorithm: &Algorithm) -> Self {
+        Cube::from_algorithm(&algorithm.symmetric(&DATA_FRAME).reversed())
+    }
+
+    // the colors of the piece at a last layer position, face by face
+    fn colors_at(&self, faces: &[u8]) -> Vec<Face> {
+        let face = |i: usize| Face::new(faces[i]);
+        match faces.len() {
+            2 => {
+                let edge = self.edge_at(pos!(face(0), face(1)));
+                (0..2).map(|i| edge.id_on(face(i))).collect()
+            }
+            _ => {
+                let corner = self.corner_at(pos!(face(0), face(1), face(2)));
+                (0..3).map(|i| corner.id_on(face(i))).collect()
+            }
+        }
+    }
+
+    // None unless all the last layer pieces are in the last layer
+    pub(crate) fn oll_key(&self) -> Option<Vec<usize>> {
+        let mut key = vec![];
+        let mut sticker = 0;
+        for faces in POSITIONS.iter() {
+            let colors = self.colors_at(faces);
+            if !colors.contains(&LAST_LAYER) { return None }
+            for color in colors {
+                if color == LAST_LAYER { key.push(sticker) }
+                sticker += 1;
+            }
+        }
+        Some(key)
+    }
+
+    // the case of an oll key with the rest of the cube solved
+    pub(crate) fn from_oll_key(key: &[usize]) -> Self {
+        Cube::solved().oriented(key)
+    }
+
+    // the pieces of the last layer turned in place as in an oll key
+    fn oriented(mut self, key: &[usize]) -> Self {
+        let mut sticker = 0;
+        for faces in POSITIONS.iter() {
+            let up = key.iter()
+                .find(|s| (sticker..sticker + faces.len()).contains(*s))
+                .unwrap_or_else(|| panic!("invalid oll key {:?}", key)) - sticker;
+            let face = |i: usize| Face::new(faces[i]);
+            // turn the piece in place until the sticker faces the last layer
+            while self.colors_at(faces)[up] != LAST_LAYER {
+                match faces.len() {
+                    2 => {
+                        let edge = self.edge_at(pos!(face(0), face(1)));
+                        self.set_edge(&Edge::new(edge.id, EdgePosition(edge.pos.1, edge.pos.0)));
+                    }
+                    _ => self.rotate_corner_clockwise_illegal(pos!(face(0), face(1), face(2)), 1),
+                }
+            }
+            sticker += faces.len();
+        }
+        self
+    }
+
+    // the case of a pll key with the rest of the cube solved
+    pub(crate) fn from_pll_key(key: &[usize]) -> Self {
+        let mut cube = Cube::solved();
+        for (piece, position) in key.iter().enumerate() {
+            let (home, faces) = (POSITIONS[piece], POSITIONS[*position]);
+            let face = |i: usize| Face::new(faces[i]);
+            // the piece where a turn of the last layer puts it
+            let turned = (0..4)
+                .map(|t| Cube::from_algorithm(&auf(t)))
+                .find(|turned| {
+                    let mut colors = turned.colors_at(faces);
+                    colors.sort();
+                    home.iter().map(|f| Face::new(*f)).eq(colors)
+                })
+                .unwrap_or_else(|| panic!("invalid pll key {:?}", key));
+            match faces.len() {
+                2 => cube.set_edge(&turned.edge_at(pos!(face(0), face(1)))),
+                _ => cube.set_corner(&turned.corner_at(pos!(face(0), face(1), face(2)))),
+            }
+        }
+        cube
+    }
+
+    // the pieces of the last layer only told apart by their orientation, this is the
+    // same for all the cubes of an oll case (with the same aufs)
+    pub(crate) fn last_layer_orientation(&self) -> Self {
+        let mut cube = *self;
+        cube.cp.iter_mut().filter(|p| LAST_LAYER_SLOTS.contains(p)).for_each(|p| *p = LAST_LAYER_SLOTS.start);
+        cube.ep.iter_mut().filter(|p| LAST_LAYER_SLOTS.contains(p)).for_each(|p| *p = LAST_LAYER_SLOTS.start);
+        cube
+    }
+
+    // the edges of the last layer only told apart by their orientation, the same for all
+    // the cubes of a coll case
+    pub(crate) fn last_layer_corners(&self) -> Self {
+        let mut cube = *self;
+        cube.ep.iter_mut().filter(|p| LAST_LAYER_SLOTS.contains(p)).for_each(|p| *p = LAST_LAYER_SLOTS.start);
+        cube
+    }
+
+    // every state of the last layer with the rest solved, the pieces permuted and/or
+    // turned in place
+    pub(crate) fn last_layer_states(permute: bool, orient: bool) -> Vec<Self> {
+        let permutations = || -> Vec<Vec<u8>> {
+            match permute {
+                true => (0..4).map(|_| LAST_LAYER_SLOTS)
+                    .multi_cartesian_product()
+                    .filter(|p| p.iter().unique().count() == 4)
+                    .collect(),
+                false => vec![LAST_LAYER_SLOTS.collect()],
+            }
+        };
+        // only the solvable ones: no twist or flip in total, as many swaps of corners as
+        // of edges (see is_solvable)
+        let orientations = |n: u8| -> Vec<Vec<u8>> {
+            match orient {
+                true => (0..4).map(|_| 0..n)
+                    .multi_cartesian_product()
+                    .filter(|o| o.iter().sum::<u8>() % n == 0)
+                    .collect(),
+                false => vec![vec![0; 4]],
+            }
+        };
+        let parity = |p: &[u8]| p.iter().tuple_combinations().filter(|(a, b)| a > b).count() % 2;
+        let mut states = vec![];
+        for (cp, co) in permutations().into_iter().cartesian_product(orientations(3)) {
+            for (ep, eo) in permutations().into_iter().cartesian_product(orientations(2)) {
+                if parity(&cp) != parity(&ep) { continue }
+                let mut cube = Cube::solved();
+                cube.cp[4..].copy_from_slice(&cp);
+                cube.co[4..].copy_from_slice(&co);
+                cube.ep[4..8].copy_from_slice(&ep);
+                cube.eo[4..8].copy_from_slice(&eo);
+                states.push(cube);
+            }
+        }
+        states
+    }
+
+    pub(crate) fn pll_key(&self) -> Option<Vec<usize>> {
+        let mut key = vec![0; 8];
+        for (position, faces) in POSITIONS.iter().enumerate() {
+            let mut colors = self.colors_at(faces);
+            colors.sort();
+            let piece = POSITIONS.iter()
+                .position(|p| p.iter().map(|f| Face::new(*f)).eq(colors.iter().cloned()))?;
+            key[piece] = position;
+        }
+        Some(key)
+    }
+
+    // the case of a step with the last layer on face 3, the layer is turned before (and
+    // after) the algorithm as the step allows, winter variation and vls are found for
+    // the pair of any slot
+    pub fn last_layer_case(&self, step: Step) -> Option<LastLayerCase> {
+        if let Step::Wv | Step::Vls = step {
+            return FRONTS.iter().map(|f| Face::new(*f)).find_map(|front| {
+                let normalized = self.transposed(cpos!(front, 3), cpos!(1, 3));
+                let key = step.case_key(&normalized)?;
+                let alg = step.data().get(&key)?;
+                let rotation = Symmetry::rotation(cpos!(1, 3), cpos!(front, 3));
+                Some(LastLayerCase {
+                    name: step.name(&key),
+                    algorithm: alg.symmetric(&DATA_FRAME).symmetric(&rotation),
+                    key,
+                    pre_auf: 0,
+                    post_auf: 0,
+                })
+            });
+        }
+
+        step.aufs().into_iter().find_map(|(pre_auf, post_auf)| {
+            let key = step.case_key(&turned(self, pre_auf, post_auf))?;
+            let alg = step.data().get(&key)?;
+            // an algorithm which solves the layer may leave it turned any way
+            let post_auf = match step {
+                Step::Pll | Step::Zbll => {
+                    let mut cube = *self;
+                    cube.apply(&(auf(pre_auf) + alg.symmetric(&DATA_FRAME)));
+                    (0..4).find(|t| (cube * AUFS[*t as usize]).pll_key().as_deref() == Some(PLL_NAMES[0].0))
+                        .unwrap_or(post_auf)
+                }
+                _ => post_auf,
+            };
+            Some(LastLayerCase::new(step, key, alg, pre_auf, post_auf))
+        })
+    }
+
+    // the oll case of the last layer (face 3), the last layer is turned until the
+    // orientation matches an entry of oll.txt
+    pub fn oll_case(&self) -> Option<LastLayerCase> {
+        self.last_layer_case(Step::Oll)
+    }
+
+    // the pll case of an oriented last layer, the layer may have to be turned before
+    // and after the algorithm
+    pub fn pll_case(&self) -> Option<LastLayerCase> {
+        self.last_layer_case(Step::Pll)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::cube::{Cube, Edge, Corner};
+use crate::cube::piece::Piece;
+use crate::cube::piece::face::Face;
+use crate::cube::transpose::{Transpose, Transposed, Projection};
+
+// pieces which have to be home (and oriented), the predefined masks are for a
+// cross on face 0
+#[derive(Clone, Debug)]
+pub struct Mask {
+    pub edges: Vec<Edge>,
+    pub corners: Vec<Corner>,
+}
+
+#[allow(dead_code)]
+impl Mask {
+    pub fn cross() -> Self {
+        Self {
+            edges: vec![edge!(0, 1), edge!(0, 2), edge!(0, 4), edge!(0, 5)],
+            corners: vec![],
+        }
+    }
+
+    // the cross and its corners
+    pub fn face() -> Self {
+        let mut mask = Self::cross();
+        mask.corners = vec![corner!(0, 1, 2), corner!(0, 2, 4), corner!(0, 4, 5), corner!(0, 1, 5)];
+        mask
+    }
+
+    pub fn f2l() -> Self {
+        let mut mask = Self::face();
+        mask.edges.extend(vec![edge!(1, 2), edge!(2, 4), edge!(4, 5), edge!(1, 5)]);
+        mask
+    }
+
+    pub fn solved() -> Self {
+        let mut mask = Self::f2l();
+        mask.edges.extend(vec![edge!(1, 3), edge!(2, 3), edge!(4, 3), edge!(5, 3)]);
+        mask.corners.extend(vec![corner!(1, 2, 3), corner!(1, 3, 5), corner!(2, 3, 4), corner!(3, 4, 5)]);
+        mask
+    }
+
+    // the same mask for a cross on another face
+    pub fn on(&self, cross: Face) -> Self {
+        let down = |face: Face| face.adjacent_clockwise()[0];
+        self.transposed(cpos!(0, down(Face::new(0))), cpos!(cross, down(cross)))
+    }
+}
+
+impl Transpose for Mask {
+    fn transpose_with_projection(&mut self, from: Projection, to: Projection) {
+        self.edges.iter_mut().for_each(|e| e.transpose_with_projection(from, to));
+        self.corners.iter_mut().for_each(|c| c.transpose_with_projection(from, to));
+    }
+}
+
+// stages of the cfop method, for a cross on any face
+#[allow(dead_code)]
+impl Cube {
+    pub fn matches_mask(&self, mask: &Mask) -> bool {
+        mask.edges.iter().all(|e| self.edge_at(e.pos) == *e)
+            && mask.corners.iter().all(|c| self.corner_at(c.pos) == *c)
+    }
+
+    pub fn is_cross_solved(&self, cross: Face) -> bool {
+        self.matches_mask(&Mask::cross().on(cross))
+    }
+
+    pub fn is_first_layer_solved(&self, cross: Face) -> bool {
+        self.matches_mask(&Mask::face().on(cross))
+    }
+
+    pub fn is_f2l_solved(&self, cross: Face) -> bool {
+        self.matches_mask(&Mask::f2l().on(cross))
+    }
+
+    // f2l and every piece of the last layer shows the last layer's color on it
+    pub fn is_oll_solved(&self, cross: Face) -> bool {
+        let last = cross.opposite();
+        self.is_f2l_solved(cross)
+            && self.iter_edges().filter(|e| e.is_on(last)).all(|e| e.id_on(last) == last)
+            && self.iter_corners().filter(|c| c.is_on(last)).all(|c| c.id_on(last) == last)
+    }
+}
@@ -0,0 +1,144 @@
+use crate::cube::{Cube, FaceMatrix, write_net};
+use crate::cube::algorithm::Algorithm;
+use crate::cube::net::{Net, NetOptions, NetView};
+use crate::cube::mask::Mask;
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::CubePosition;
+use crate::cube::slot::{edge_index, corner_index};
+use std::fmt::{Debug, Display, Formatter, Error};
+use std::hash::{Hash, Hasher};
+
+// (slot, orientation) of pieces
+type Placement = Vec<(u8, u8)>;
+
+// a cube of which only some pieces are known, the others are wildcards, known
+// pieces are tracked by identity so they stay known wherever they are moved
+#[derive(Copy, Clone)]
+pub struct MaskedCube {
+    cube: Cube,
+    // bit i is set if piece i is known
+    edges: u16,
+    corners: u8,
+}
+
+#[allow(dead_code)]
+impl MaskedCube {
+    // only the pieces of the mask are known
+    pub fn new(cube: Cube, mask: &Mask) -> Self {
+        let edges = mask.edges.iter().fold(0, |bits, e| bits | 1 << edge_index(e.id));
+        let corners = mask.corners.iter().fold(0, |bits, c| bits | 1 << corner_index(c.id));
+        Self { cube, edges, corners }
+    }
+
+    pub fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    pub fn apply(&mut self, algorithm: &Algorithm) {
+        self.cube.apply(algorithm)
+    }
+
+    fn is_edge_known(&self, piece: u8) -> bool {
+        self.edges & 1 << piece != 0
+    }
+
+    fn is_corner_known(&self, piece: u8) -> bool {
+        self.corners & 1 << piece != 0
+    }
+
+    // the slot and orientation of every known piece
+    fn known_pieces(&self) -> (Placement, Placement) {
+        let inverse = self.cube.inverse();
+        let edges = (0..12)
+            .filter(|p| self.is_edge_known(*p))
+            .map(|p| (inverse.ep[p as usize], inverse.eo[p as usize]))
+            .collect();
+        let corners = (0..8)
+            .filter(|p| self.is_corner_known(*p))
+            .map(|p| (inverse.cp[p as usize], inverse.co[p as usize]))
+            .collect();
+        (edges, corners)
+    }
+
+    // the known pieces of self are where they are in the cube
+    pub fn matches(&self, cube: &Cube) -> bool {
+        *self == MaskedCube { cube: *cube, ..*self }
+    }
+
+    // None if the piece at the position is a wildcard
+    fn sticker(&self, position: &[Face]) -> Option<Face> {
+        let known = match *position {
+            [_] => true,
+            [f, f1] => self.is_edge_known(self.cube.ep[edge_index(pos!(f, f1))]),
+            [f, f1, f2] => self.is_corner_known(self.cube.cp[corner_index(pos!(f, f1, f2))]),
+            _ => unreachable!(),
+        };
+        if known { Some(self.cube.sticker(position)) } else { None }
+    }
+
+    pub fn colored(&self) -> NetView<'_> {
+        NetView::new(self, NetOptions::default())
+    }
+}
+
+impl Net for MaskedCube {
+    fn face(&self, position: CubePosition) -> FaceMatrix<Option<Face>> {
+        FaceMatrix::from(|p| self.sticker(p), position)
+    }
+}
+
+// masked cubes are equal if they know the same pieces and these are in the same
+// places, the wildcards can be anywhere
+impl PartialEq for MaskedCube {
+    fn eq(&self, other: &Self) -> bool {
+        self.edges == other.edges
+            && self.corners == other.corners
+            && self.known_pieces() == other.known_pieces()
+    }
+}
+
+impl Eq for MaskedCube {}
+
+impl Hash for MaskedCube {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.edges.hash(state);
+        self.corners.hash(state);
+        self.known_pieces().hash(state);
+    }
+}
+
+struct Sticker(Option<Face>);
+
+impl Display for Sticker {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self.0 {
+            Some(face) => write!(f, "{}", face),
+            None => write!(f, "."),
+        }
+    }
+}
+
+impl Debug for Sticker {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self.0 {
+            Some(face) => write!(f, "{:?}", face),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+impl Display for MaskedCube {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write_net(f, |position| {
+            format!("{}", FaceMatrix::from(|p| Sticker(self.sticker(p)), position))
+        })
+    }
+}
+
+impl Debug for MaskedCube {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write_net(f, |position| {
+            format!("{:?}", FaceMatrix::from(|p| Sticker(self.sticker(p)), position))
+        })
+    }
+}
@@ -3,13 +3,15 @@ pub use piece::{edge::Edge, corner::Corner};
 use piece::face::Face;
 
 use std::fmt::{Debug, Formatter, Error, Display};
-use crate::cube::transpose::{Transpose, Projection};
+use std::ops::{Deref, DerefMut};
+use crate::cube::transpose::{Transpose, Transposed, Projection};
 use piece::position::{EdgePosition, CornerPosition};
 use itertools::Itertools;
 use crate::cube::algorithm::{Algorithm, Move};
 use crate::cube::piece::Piece;
 use crate::cube::piece::position::CubePosition;
-use std::iter::once;
+use symmetry::Symmetry;
+use slot::{edge_index, corner_index, make_edge, make_corner, locate_edge, locate_corner};
 
 #[macro_use]
 pub mod piece;
@@ -21,42 +23,104 @@ mod resort;
 pub mod algorithm;
 
 mod manipulation;
-mod color;
-mod solving;
-
-#[derive(Eq, PartialEq, Copy, Clone)]
+pub mod color;
+pub(crate) mod solving;
+pub mod scramble;
+pub mod coordinate;
+mod group;
+mod slot;
+mod move_table;
+pub mod packed;
+pub mod symmetry;
+pub mod last_layer;
+pub mod f2l;
+pub mod mask;
+pub mod masked;
+pub mod nxn;
+pub mod pocket;
+pub mod supercube;
+pub mod svg;
+pub mod top_view;
+pub mod isometric;
+pub mod net;
+
+// pieces are stored by slot (see slot.rs): which piece is in each slot and how it's
+// oriented relative to the slot
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
 pub struct Cube {
-    edges: [Edge; 12],
-    corners: [Corner; 8],
+    cp: [u8; 8],
+    co: [u8; 8],
+    ep: [u8; 12],
+    eo: [u8; 12],
+    // clockwise quarter turns of every center, only tracked on supercubes (see
+    // supercube.rs), a supercube is never equal to a cube without centers
+    centers: Option<[u8; 6]>,
 }
 
-pub struct FaceMatrix([[Face; 3]; 3]);
-
-impl FaceMatrix {
-    fn from(cube: &Cube, f: Face, d: Face, l: Face, u: Face, r: Face) -> Self {
-        macro_rules! f {
-            ($f1:expr) => {
-                cube.edge_at(pos!(f, $f1)).id_on(f)
-            };
-            ($f1:expr, $f2:expr) => {
-                cube.corner_at(pos!(f, $f1, $f2)).id_on(f)
-            };
-        }
+pub struct FaceMatrix<T = Face>(Vec<Vec<T>>);
+
+// a piece taken out of a cube to be changed, it's put back where it then says it is
+// when dropped
+#[allow(dead_code)]
+pub struct PieceMut<'a, P> {
+    cube: &'a mut Cube,
+    piece: P,
+    put: fn(&mut Cube, &P),
+}
+
+impl<P> Deref for PieceMut<'_, P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.piece
+    }
+}
 
-        Self([
-            [f!(l, u), f!(u), f!(r, u)],
-            [f!(l)   , f    , f!(r)   ],
-            [f!(l, d), f!(d), f!(r, d)],
+impl<P> DerefMut for PieceMut<'_, P> {
+    fn deref_mut(&mut self) -> &mut P {
+        &mut self.piece
+    }
+}
+
+impl<P> Drop for PieceMut<'_, P> {
+    fn drop(&mut self) {
+        (self.put)(self.cube, &self.piece)
+    }
+}
+
+// the faces below, left of, above and right of the front of a position
+fn surrounding(position: CubePosition) -> [Face; 4] {
+    let CubePosition { front: f, down: d } = position;
+
+    let mut adjacent_clockwise = f.adjacent_clockwise();
+    let mid = adjacent_clockwise
+        .iter()
+        .position(|x| *x == d)
+        .unwrap();
+    adjacent_clockwise.rotate_left(mid);
+    adjacent_clockwise
+}
+
+impl<T> FaceMatrix<T> {
+    // sticker(position) is what to show on the first face of the position
+    fn from(sticker: impl Fn(&[Face]) -> T, position: CubePosition) -> Self {
+        let f = position.front;
+        let [d, l, u, r] = surrounding(position);
+
+        Self(vec![
+            vec![sticker(&[f, l, u]), sticker(&[f, u]), sticker(&[f, r, u])],
+            vec![sticker(&[f, l])   , sticker(&[f])   , sticker(&[f, r])   ],
+            vec![sticker(&[f, l, d]), sticker(&[f, d]), sticker(&[f, r, d])],
         ])
     }
 }
 
 macro_rules! impl_face_matrix_fmt {
-    ($trait:ty, $fmt:expr) => {
-        impl $trait for FaceMatrix {
+    ($trait:ident, $fmt:expr) => {
+        impl<T: $trait> $trait for FaceMatrix<T> {
             fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
                 for line in &self.0 {
-                    writeln!(f, $fmt, line[0], line[1], line[2])?
+                    writeln!(f, "{}", line.iter().map(|s| format!($fmt, s)).join(" "))?
                 }
                 Ok(())
             }
@@ -64,122 +128,110 @@ macro_rules! impl_face_matrix_fmt {
     };
 }
 
-impl_face_matrix_fmt!(Debug, "{:?} {:?} {:?}");
-impl_face_matrix_fmt!(Display, "{} {} {}");
+impl_face_matrix_fmt!(Debug, "{:?}");
+impl_face_matrix_fmt!(Display, "{}");
+
+// current representation
+//   0
+// 5 1 2 4
+//   3
+fn write_net(f: &mut Formatter, format_face: impl Fn(CubePosition) -> String) -> Result<(), Error> {
+    let top = format_face(cpos!(0, 1));
+    let indent = " ".repeat(top.lines().next().map_or(0, |l| l.len()) + 2);
+    let push_right = |face: String| face.lines()
+        .map(|l| [indent.as_str(), l].join(""))
+        .join("\n");
+
+    writeln!(f, "{}\n", push_right(top))?;
+
+    let central_band = format_face(cpos!(5, 3)).lines()
+        .zip(format_face(cpos!(1, 3)).lines())
+        .zip(format_face(cpos!(2, 3)).lines())
+        .zip(format_face(cpos!(4, 3)).lines())
+        .map(|(((b, c), d), e)|
+            [b, c, d, e].join("  ")
+        ).join("\n");
+
+    writeln!(f, "{}\n", central_band)?;
+
+    writeln!(f, "{}", push_right(format_face(cpos!(3, 4))))
+}
 
 // TODO: test printing
 // TODO: implement a way to numerically input a cube
 // TODO: implement a way to textually input a cube, test
 // TODO: test algorithm application on cube
 
-static mut SOLVED_CUBE: Option<Cube> = None;
+const SOLVED_CUBE: Cube = Cube {
+    cp: [0, 1, 2, 3, 4, 5, 6, 7],
+    co: [0; 8],
+    ep: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+    eo: [0; 12],
+    centers: None,
+};
 
 impl Cube {
-    fn generate_solved() -> Self {
-        let edges_on_0 = Face::from(0).adjacent_edges();
-        let edges_on_3 = Face::from(3).adjacent_edges();
-
-        let adjacent = Face::from(0).adjacent();
-        let edges_around_iter = adjacent.iter()
-            .map(|f| *f)
-            .chain(once(adjacent[0]))
-            .tuple_windows()
-            .map(|(f0, f1)| edge!(f0, f1));
-        let edges_around = array_collect!(edges_around_iter, [Edge; 4]);
-
-        let edges_iter = edges_on_0.iter()
-            .chain(&edges_on_3)
-            .chain(&edges_around)
-            .map(|e| *e);
-
-        let corners_on_0 = Face::from(0).adjacent_corners();
-        let corners_on_3 = Face::from(3).adjacent_corners();
-
-        let corners_iter = corners_on_0.iter()
-            .chain(&corners_on_3)
-            .map(|c| *c);
-
-        Self {
-            edges: array_collect!(edges_iter, [Edge; 12]),
-            corners: array_collect!(corners_iter, [Corner; 8]),
-        }
-    }
-
     pub fn solved() -> Self {
-        unsafe {
-            if SOLVED_CUBE.is_none() {
-                SOLVED_CUBE = Some(Self::generate_solved())
-            }
-            SOLVED_CUBE.unwrap()
-        }
+        SOLVED_CUBE
     }
 
+    // the centers of a supercube have to be solved too
     #[allow(dead_code)]
     pub fn is_solved(&self) -> bool {
-        *self == Self::solved()
+        Self { centers: None, ..*self } == Self::solved()
+            && self.centers.unwrap_or([0; 6]) == [0; 6]
     }
 
-    pub fn edge_at(&self, position: EdgePosition) -> &Edge {
-        match self.edges.iter().find(|e| e.is_at(position)) {
-            Some(e) => e,
-            None => unreachable!("no edge at {:?}", position)
-        }
+    pub fn edge_at(&self, position: EdgePosition) -> Edge {
+        let slot = edge_index(position);
+        make_edge(slot, self.ep[slot], self.eo[slot])
     }
 
-    pub fn corner_at(&self, position: CornerPosition) -> &Corner {
-        match self.corners.iter().find(|c| c.is_at(position)) {
-            Some(c) => c,
-            None => unreachable!("no corner at {:?}", position)
-        }
+    pub fn corner_at(&self, position: CornerPosition) -> Corner {
+        let slot = corner_index(position);
+        make_corner(slot, self.cp[slot], self.co[slot])
     }
 
-    pub fn corner_at_mut(&mut self, position: CornerPosition) -> &mut Corner {
-        match self.corners.iter_mut().find(|c| c.is_at(position)) {
-            Some(c) => c,
-            None => unreachable!("no corner at {:?}", position)
-        }
+    // the corner stays in the cube as it was until the returned piece is dropped
+    #[allow(dead_code)]
+    pub fn corner_at_mut(&mut self, position: CornerPosition) -> PieceMut<'_, Corner> {
+        let piece = self.corner_at(position);
+        PieceMut { cube: self, piece, put: Cube::set_corner }
     }
 
-    fn get_face_matrix(&self, position: CubePosition) -> FaceMatrix {
-        let CubePosition { front: f, down: d } = position;
+    // put a piece where it says it is
+    pub(crate) fn set_edge(&mut self, edge: &Edge) {
+        let (slot, piece, orientation) = locate_edge(edge);
+        self.ep[slot] = piece;
+        self.eo[slot] = orientation;
+    }
 
-        let mut adjacent_clockwise = f.adjacent_clockwise();
-        let mid = adjacent_clockwise
-            .iter()
-            .position(|x| *x == d)
-            .unwrap();
-        adjacent_clockwise.rotate_left(mid);
+    pub(crate) fn set_corner(&mut self, corner: &Corner) {
+        let (slot, piece, orientation) = locate_corner(corner);
+        self.cp[slot] = piece;
+        self.co[slot] = orientation;
+    }
 
-        let [d, l, u, r] = adjacent_clockwise;
+    // the color on the first face of the position
+    fn sticker(&self, position: &[Face]) -> Face {
+        match *position {
+            [f] => f,
+            [f, f1] => self.edge_at(pos!(f, f1)).id_on(f),
+            [f, f1, f2] => self.corner_at(pos!(f, f1, f2)).id_on(f),
+            _ => unreachable!(),
+        }
+    }
 
-        FaceMatrix::from(self, f, d, l, u, r)
+    fn get_face_matrix(&self, position: CubePosition) -> FaceMatrix {
+        FaceMatrix::from(|p| self.sticker(p), position)
     }
 
     fn apply_move(&mut self, m: &Move) {
-        let (face, times) = (m.face(), m.times());
-        let clockwise = face.adjacent_clockwise();
-        self.edges
-            .iter_mut()
-            .filter(|e| e.is_on(face))
-            .for_each(|edge| {
-                let missing = edge.position_without(face);
-                let index: usize = clockwise.iter().position(
-                    |x| *x == missing).unwrap();
-                let next = clockwise[(index + times as usize) % clockwise.len()];
-                edge.transpose_pos(cpos!(face, missing), cpos!(face, next));
-            });
-
-        self.corners
-            .iter_mut()
-            .filter(|c| c.is_on(face))
-            .for_each(|corner| {
-                let missing = corner.position_without(face).0;
-                let index: usize = clockwise.iter().position(
-                    |x| *x == missing
-                ).unwrap();
-                let next = clockwise[(index + times as usize) % clockwise.len()];
-                corner.transpose_pos(cpos!(face, missing), cpos!(face, next));
-            });
+        *self = *self * move_table::get(m);
+        if let Some(centers) = &mut self.centers {
+            let face = m.face().index();
+            centers[face] = (centers[face] + m.times()) % 4;
+        }
     }
 
     #[allow(dead_code)]
@@ -193,36 +245,51 @@ impl Cube {
 // iteration
 #[allow(dead_code)]
 impl Cube {
-    pub fn iter_edges(&self) -> impl Iterator<Item=&Edge> { self.edges.iter() }
-    pub fn iter_corners(&self) -> impl Iterator<Item=&Corner> { self.corners.iter() }
-    pub fn iter_pieces(&self) -> impl Iterator<Item=&dyn Piece> {
+    pub fn iter_edges(&self) -> impl Iterator<Item=Edge> + '_ {
+        (0..12).map(move |slot| make_edge(slot, self.ep[slot], self.eo[slot]))
+    }
+
+    pub fn iter_corners(&self) -> impl Iterator<Item=Corner> + '_ {
+        (0..8).map(move |slot| make_corner(slot, self.cp[slot], self.co[slot]))
+    }
+
+    pub fn iter_pieces(&self) -> impl Iterator<Item=Box<dyn Piece>> + '_ {
         self.iter_edges()
-            .map(|e| e as &dyn Piece)
+            .map(|e| Box::new(e) as Box<dyn Piece>)
             .chain(
                 self.iter_corners()
-                    .map(|c| c as &dyn Piece)
+                    .map(|c| Box::new(c) as Box<dyn Piece>)
             )
     }
-    pub fn iter_edges_mut(&mut self) -> impl Iterator<Item=&mut Edge> { self.edges.iter_mut() }
-    pub fn iter_corners_mut(&mut self) -> impl Iterator<Item=&mut Corner> {
-        self.corners.iter_mut()
-    }
 
-    pub fn iter_pieces_on<F: Into<Face>>(&self, face: F) -> impl Iterator<Item=&dyn Piece> {
+    pub fn iter_pieces_on<F: Into<Face>>(&self, face: F) -> impl Iterator<Item=Box<dyn Piece>> + '_ {
         let face = face.into();
-        self.iter_pieces().filter(move |p: &&dyn Piece| p.is_on(face))
+        self.iter_pieces().filter(move |p| p.is_on(face))
     }
 }
 
 impl Transpose for Cube {
     fn transpose_with_projection(&mut self, from: Projection, to: Projection) {
-        self.edges
-            .iter_mut()
-            .for_each(|e| e.transpose_with_projection(from, to));
+        let mut transposed = *self;
 
-        self.corners
-            .iter_mut()
-            .for_each(|c| c.transpose_with_projection(from, to));
+        self.iter_edges()
+            .for_each(|e| transposed.set_edge(&e.transposed_with_projection(from, to)));
+
+        self.iter_corners()
+            .for_each(|c| transposed.set_corner(&c.transposed_with_projection(from, to)));
+
+        // centers move with their faces, a reflection turns them the other way
+        if let Some(centers) = self.centers {
+            let symmetry = Symmetry::between(from, to);
+            let mut moved = [0; 6];
+            for (face, turns) in centers.iter().enumerate() {
+                let image = symmetry.image(Face::new(face as u8)).index();
+                moved[image] = if symmetry.is_reflection() { (4 - turns) % 4 } else { *turns };
+            }
+            transposed.centers = Some(moved);
+        }
+
+        *self = transposed;
     }
 }
 
@@ -230,42 +297,11 @@ macro_rules! impl_cube_fmt {
     ($trait:ty, $fmt:expr) => {
         impl $trait for Cube {
             fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-                macro_rules! format_face {
-                    ($face:expr, $below:expr) => {
-                        format!($fmt, self.get_face_matrix(cpos!($face, $below)))
-                    };
-                }
-
-                macro_rules! push_right {
-                    ($face:expr) => {
-                        $face.lines().map(|l| {
-                            vec!["       ", l].join("")
-                        }).collect::<Vec<_>>().join("\n");
-                    }
-                }
-
-                // current representation
-                //   0
-                // 5 1 2 4
-                //   3
-
-                writeln!(f, "{}\n", push_right!(format_face!(0, 1)))?;
-
-                let central_band = format_face!(5, 3).lines()
-                    .zip(format_face!(1, 3).lines())
-                    .zip(format_face!(2, 3).lines())
-                    .zip(format_face!(4, 3).lines())
-                    .map(|(((b, c), d), e)|
-                        vec![b, c, d, e].join("  ")
-                    ).join("\n");
-
-                writeln!(f, "{}\n", central_band)?;
-
-                writeln!(f, "{}", push_right!(format_face!(3, 4)))
+                write_net(f, |position| format!($fmt, self.get_face_matrix(position)))
             }
         }
     }
 }
 
 impl_cube_fmt!(Debug, "{:?}");
-impl_cube_fmt!(Display, "{}");
\ No newline at end of file
+impl_cube_fmt!(Display, "{}");
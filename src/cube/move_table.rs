@@ -0,0 +1,56 @@
+use crate::cube::Cube;
+use crate::cube::algorithm::Move;
+use crate::cube::piece::Piece;
+use crate::cube::piece::face::Face;
+use crate::cube::slot::{make_edge, make_corner};
+
+lazy_static! {
+    // MOVE_TABLE[face][times - 1] is the state reached by applying the move to a
+    // solved cube, applying a move is then a single multiplication
+    static ref MOVE_TABLE: [[Cube; 3]; 6] = {
+        let mut table = [[Cube::solved(); 3]; 6];
+        for (face, moves) in table.iter_mut().enumerate() {
+            let quarter = quarter_turn(Face::new(face as u8));
+            moves[0] = quarter;
+            moves[1] = quarter * quarter;
+            moves[2] = quarter * quarter * quarter;
+        }
+        table
+    };
+}
+
+pub(crate) fn get(m: &Move) -> Cube {
+    MOVE_TABLE[m.face().index()][m.times() as usize - 1]
+}
+
+// turn every piece on the face individually, this is slow and only used to build
+// the table
+fn quarter_turn(face: Face) -> Cube {
+    let clockwise = face.adjacent_clockwise();
+    let next = |missing: Face| {
+        let index = clockwise.iter().position(|x| *x == missing).unwrap();
+        clockwise[(index + 1) % clockwise.len()]
+    };
+
+    let mut cube = Cube::solved();
+
+    (0..12)
+        .map(|slot| make_edge(slot, slot as u8, 0))
+        .filter(|e| e.is_on(face))
+        .for_each(|mut edge| {
+            let missing = edge.position_without(face);
+            edge.transpose_pos(cpos!(face, missing), cpos!(face, next(missing)));
+            cube.set_edge(&edge);
+        });
+
+    (0..8)
+        .map(|slot| make_corner(slot, slot as u8, 0))
+        .filter(|c| c.is_on(face))
+        .for_each(|mut corner| {
+            let missing = corner.position_without(face).0;
+            corner.transpose_pos(cpos!(face, missing), cpos!(face, next(missing)));
+            cube.set_corner(&corner);
+        });
+
+    cube
+}
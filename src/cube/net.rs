@@ -0,0 +1,161 @@
+use crate::cube::{Cube, FaceMatrix};
+use crate::cube::color::ColorScheme;
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::CubePosition;
+use crate::cube::svg::NET;
+use std::fmt::{Debug, Display, Formatter, Error};
+use std::io::IsTerminal;
+
+// anything which can be shown as a net, faces can have any size
+pub trait Net {
+    // the face seen from the position, None for unknown stickers
+    fn face(&self, position: CubePosition) -> FaceMatrix<Option<Face>>;
+}
+
+impl Net for Cube {
+    fn face(&self, position: CubePosition) -> FaceMatrix<Option<Face>> {
+        FaceMatrix::from(|p| Some(self.sticker(p)), position)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NetMode {
+    // the letters of the scheme
+    Ascii,
+    // colored squares
+    Unicode,
+    // the colors of the scheme in the terminal
+    Ansi,
+}
+
+impl NetMode {
+    // ansi when stdout is a terminal, plain letters otherwise
+    pub fn detect() -> Self {
+        if std::io::stdout().is_terminal() { NetMode::Ansi } else { NetMode::Ascii }
+    }
+
+    fn sticker(self, scheme: &ColorScheme, face: Option<Face>) -> String {
+        match self {
+            NetMode::Ascii => scheme.color(face).letter.to_string(),
+            NetMode::Unicode => match face {
+                Some(_) => scheme.color(face).emoji().to_string(),
+                None => "⬛".to_string(),
+            },
+            NetMode::Ansi => scheme.paint("  ", face),
+        }
+    }
+
+    // in columns
+    fn sticker_width(self) -> usize {
+        match self {
+            NetMode::Ascii => 1,
+            NetMode::Unicode | NetMode::Ansi => 2,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct NetOptions {
+    pub mode: NetMode,
+    pub scheme: ColorScheme,
+    // columns between the stickers of a face
+    pub sticker_spacing: usize,
+    // lines between the faces, twice as many columns
+    pub face_spacing: usize,
+}
+
+impl Default for NetOptions {
+    fn default() -> Self {
+        Self::new(NetMode::detect())
+    }
+}
+
+#[allow(dead_code)]
+impl NetOptions {
+    // ascii is spaced like Display, the others are solid
+    pub fn new(mode: NetMode) -> Self {
+        let sticker_spacing = if mode == NetMode::Ascii { 1 } else { 0 };
+        Self { mode, scheme: ColorScheme::default(), sticker_spacing, face_spacing: 1 }
+    }
+
+    pub fn with_scheme(self, scheme: ColorScheme) -> Self {
+        Self { scheme, ..self }
+    }
+
+    pub fn with_spacing(self, sticker_spacing: usize, face_spacing: usize) -> Self {
+        Self { sticker_spacing, face_spacing, ..self }
+    }
+}
+
+//   0
+// 5 1 2 4
+//   3
+pub struct NetView<'a> {
+    net: &'a dyn Net,
+    options: NetOptions,
+}
+
+#[allow(dead_code)]
+impl<'a> NetView<'a> {
+    pub fn new(net: &'a dyn Net, options: NetOptions) -> Self {
+        Self { net, options }
+    }
+
+    pub fn with_options(self, options: NetOptions) -> Self {
+        Self { options, ..self }
+    }
+}
+
+impl Display for NetView<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let NetOptions { mode, scheme, sticker_spacing, face_spacing } = self.options;
+        let faces = NET.iter()
+            .map(|((front, down), column, row)| ((*column, *row), self.net.face(cpos!(*front, *down)).0))
+            .collect::<Vec<_>>();
+        let size = faces[0].1.len();
+        let face_width = size * mode.sticker_width() + (size - 1) * sticker_spacing;
+
+        for row in 0..3 {
+            if row > 0 {
+                write!(f, "{}", "\n".repeat(face_spacing))?;
+            }
+            let band = faces.iter().filter(|((_, r), _)| *r == row).collect::<Vec<_>>();
+            for line in 0..size {
+                let mut column = 0;
+                for ((c, _), matrix) in &band {
+                    // the empty faces on the left
+                    let skipped = (*c - column) as usize;
+                    let indent = if column == 0 { 0 } else { 2 * face_spacing };
+                    write!(f, "{}", " ".repeat(indent + skipped * (face_width + 2 * face_spacing)))?;
+                    let stickers = matrix[line].iter()
+                        .map(|sticker| mode.sticker(&scheme, *sticker))
+                        .collect::<Vec<_>>();
+                    write!(f, "{}", stickers.join(&" ".repeat(sticker_spacing)))?;
+                    column = c + 1;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// just for convenience, TODO: remove
+impl Debug for NetView<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        Display::fmt(self, f)
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // in color when stdout is a terminal
+    pub fn colored(&self) -> NetView<'_> {
+        self.net(NetOptions::default())
+    }
+
+    pub fn net(&self, options: NetOptions) -> NetView<'_> {
+        NetView::new(self, options)
+    }
+}
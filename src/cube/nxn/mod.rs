@@ -0,0 +1,208 @@
+use crate::cube::{FaceMatrix, surrounding, write_net};
+use crate::cube::net::{Net, NetOptions, NetView};
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::{CornerPosition, CubePosition, EdgePosition};
+use crate::cube::symmetry::FACE_VECTORS;
+use std::fmt::{Debug, Display, Formatter, Error};
+
+pub mod notation;
+
+use notation::{LayerAlgorithm, LayerMove};
+
+type Vector = [i32; 3];
+
+fn vector(face: Face) -> Vector {
+    let [x, y, z] = FACE_VECTORS[face.index()];
+    [x as i32, y as i32, z as i32]
+}
+
+fn face_of(v: Vector) -> Face {
+    (0..6).map(Face::new).find(|f| vector(*f) == v).unwrap()
+}
+
+fn dot(a: Vector, b: Vector) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+// a * x + b * y + c * z
+fn combine(terms: &[(Vector, i32)]) -> Vector {
+    let mut sum = [0; 3];
+    for (v, k) in terms {
+        (0..3).for_each(|i| sum[i] += v[i] * k);
+    }
+    sum
+}
+
+// a quarter turn of v, clockwise when looking at the axis from outside
+fn turned(v: Vector, axis: Vector) -> Vector {
+    combine(&[(axis, dot(axis, v)), (cross(axis, v), -1)])
+}
+
+// how every face is stored (and printed), as in the net of Cube
+fn stored_position(face: Face) -> CubePosition {
+    match face.index() {
+        0 => cpos!(0, 1),
+        3 => cpos!(3, 4),
+        _ => cpos!(face, 3),
+    }
+}
+
+// a cube of any size (from 2) stored sticker by sticker, row by row from the top
+// of every face as printed in the net; positions are doubled so that they are
+// integers, the center of the cube is the origin and stickers are at distance
+// size - 1 from it along their face's axis
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct NxnCube {
+    size: usize,
+    stickers: Vec<Face>,
+}
+
+#[allow(dead_code)]
+impl NxnCube {
+    pub fn solved(size: usize) -> Self {
+        assert!(size >= 2, "a cube has at least 2 layers, got {}", size);
+        let stickers = (0..6)
+            .flat_map(|f| vec![Face::new(f); size * size])
+            .collect();
+        Self { size, stickers }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // every face has a single color (even sized cubes have no fixed centers so
+    // the colors don't have to be on their usual faces)
+    pub fn is_solved(&self) -> bool {
+        self.stickers
+            .chunks(self.size * self.size)
+            .all(|face| face.iter().all(|s| *s == face[0]))
+    }
+
+    fn half(&self) -> i32 {
+        self.size as i32 - 1
+    }
+
+    // the doubled position of the sticker at row, col of the face seen from position
+    fn sticker_vector(&self, position: CubePosition, row: usize, col: usize) -> Vector {
+        let [_, _, u, r] = surrounding(position);
+        let half = self.half();
+        combine(&[
+            (vector(position.front), half),
+            (vector(u), half - 2 * row as i32),
+            (vector(r), 2 * col as i32 - half),
+        ])
+    }
+
+    fn index(&self, v: Vector, normal: Vector) -> usize {
+        let face = face_of(normal);
+        let [_, _, u, r] = surrounding(stored_position(face));
+        let row = (self.half() - dot(v, vector(u))) / 2;
+        let col = (dot(v, vector(r)) + self.half()) / 2;
+        (face.index() * self.size + row as usize) * self.size + col as usize
+    }
+
+    fn sticker_at(&self, v: Vector, normal: Vector) -> Face {
+        self.stickers[self.index(v, normal)]
+    }
+
+    pub fn apply_move(&mut self, m: &LayerMove) {
+        let to = m.to.min(self.size);
+        assert!(m.from <= to, "{} on a cube of size {}", m, self.size);
+
+        let axis = vector(m.face);
+        let mut stickers = self.stickers.clone();
+        for face in 0..6 {
+            let position = stored_position(Face::new(face));
+            for row in 0..self.size {
+                for col in 0..self.size {
+                    let (mut v, mut normal) = (self.sticker_vector(position, row, col), vector(Face::new(face)));
+                    let layer = ((self.half() - dot(v, axis)) / 2 + 1) as usize;
+                    if layer < m.from || layer > to { continue }
+
+                    let from = self.index(v, normal);
+                    for _ in 0..m.times {
+                        v = turned(v, axis);
+                        normal = turned(normal, axis);
+                    }
+                    stickers[self.index(v, normal)] = self.stickers[from];
+                }
+            }
+        }
+        self.stickers = stickers;
+    }
+
+    pub fn apply(&mut self, algorithm: &LayerAlgorithm) {
+        for m in algorithm {
+            self.apply_move(m)
+        }
+    }
+
+    // colors of a corner, face by face
+    pub fn corner(&self, position: CornerPosition) -> CornerPosition {
+        let CornerPosition(a, b, c) = position;
+        let v = combine(&[(vector(a), self.half()), (vector(b), self.half()), (vector(c), self.half())]);
+        pos!(self.sticker_at(v, vector(a)), self.sticker_at(v, vector(b)), self.sticker_at(v, vector(c)))
+    }
+
+    // colors of the wings of an edge (the middle edge on odd cubes), face by face, in
+    // the direction of position.0 x position.1
+    pub fn wings(&self, position: EdgePosition) -> Vec<EdgePosition> {
+        let EdgePosition(a, b) = position;
+        let along = cross(vector(a), vector(b));
+        (1..self.size - 1)
+            .map(|i| {
+                let v = combine(&[
+                    (vector(a), self.half()),
+                    (vector(b), self.half()),
+                    (along, 2 * i as i32 - self.half()),
+                ]);
+                pos!(self.sticker_at(v, vector(a)), self.sticker_at(v, vector(b)))
+            })
+            .collect()
+    }
+
+    // the inner (size - 2)² stickers of a face as printed, row by row
+    pub fn centers(&self, face: Face) -> Vec<Vec<Face>> {
+        let matrix = self.face_matrix(stored_position(face)).0;
+        matrix[1..self.size - 1].iter()
+            .map(|row| row[1..self.size - 1].to_vec())
+            .collect()
+    }
+
+    fn face_matrix(&self, position: CubePosition) -> FaceMatrix {
+        let normal = vector(position.front);
+        FaceMatrix((0..self.size)
+            .map(|row| (0..self.size)
+                .map(|col| self.sticker_at(self.sticker_vector(position, row, col), normal))
+                .collect())
+            .collect())
+    }
+
+    pub fn colored(&self) -> NetView<'_> {
+        NetView::new(self, NetOptions::default())
+    }
+}
+
+impl Net for NxnCube {
+    fn face(&self, position: CubePosition) -> FaceMatrix<Option<Face>> {
+        let FaceMatrix(rows) = self.face_matrix(position);
+        FaceMatrix(rows.into_iter().map(|row| row.into_iter().map(Some).collect()).collect())
+    }
+}
+
+impl Display for NxnCube {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write_net(f, |position| format!("{}", self.face_matrix(position)))
+    }
+}
+
+impl Debug for NxnCube {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write_net(f, |position| format!("{:?}", self.face_matrix(position)))
+    }
+}
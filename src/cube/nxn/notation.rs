@@ -0,0 +1,160 @@
+use crate::cube::algorithm::{Algorithm, MoveType};
+use crate::cube::piece::face::Face;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter, Error, Debug};
+
+// the deepest layer of a rotation, clamped to the size of the cube
+pub(crate) const ALL_LAYERS: usize = usize::MAX;
+
+// a turn of the layers from..=to of a face, layer 1 being the face itself
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct LayerMove {
+    pub(crate) face: Face,
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) times: u8,
+}
+
+// R, R', R2, 2R (second layer only), Rw and r (two layers), 3Rw (three layers), x, y, z
+impl From<&str> for LayerMove {
+    fn from(s: &str) -> Self {
+        let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+        let depth = match digits {
+            0 => None,
+            _ => Some(s[..digits].parse::<usize>().unwrap()),
+        };
+        assert_ne!(depth, Some(0), "invalid layer in move '{}'", s);
+
+        let mut chars = s[digits..].chars().peekable();
+        let letter = chars.next().unwrap_or_else(|| panic!("missing face in move '{}'", s));
+        let wide = chars.peek() == Some(&'w');
+        if wide { chars.next(); }
+
+        let (face, from, to) = match letter {
+            'x' | 'y' | 'z' => {
+                assert!(depth.is_none() && !wide, "invalid rotation '{}'", s);
+                let face = MoveType::from(match letter { 'x' => 'R', 'y' => 'U', _ => 'F' }).face();
+                (face, 1, ALL_LAYERS)
+            }
+            c if c.is_ascii_lowercase() => {
+                assert!(!wide, "invalid move '{}'", s);
+                (MoveType::from(c.to_ascii_uppercase()).face(), 1, depth.unwrap_or(2))
+            }
+            c if wide => (MoveType::from(c).face(), 1, depth.unwrap_or(2)),
+            c => {
+                let depth = depth.unwrap_or(1);
+                (MoveType::from(c).face(), depth, depth)
+            }
+        };
+
+        let times = match (chars.next(), chars.next()) {
+            (None, _) => 1,
+            (Some('2'), None) | (Some('2'), Some('\'')) => 2,
+            (Some('\''), None) => 3,
+            _ => panic!("invalid quantifier in move '{}'", s),
+        };
+        if let Some(c) = chars.next() {
+            panic!("unexpected character '{}' in move '{}'", c, s);
+        }
+
+        Self { face, from, to, times }
+    }
+}
+
+impl LayerMove {
+    pub fn face(&self) -> Face {
+        self.face
+    }
+
+    pub fn times(&self) -> u8 {
+        self.times
+    }
+
+    fn reversed(&self) -> Self {
+        Self { times: 4 - self.times, ..*self }
+    }
+}
+
+impl Display for LayerMove {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let move_type = MoveType::from(self.face);
+        let quantifier = match self.times {
+            1 => "",
+            2 => "2",
+            3 => "'",
+            _ => unreachable!(),
+        };
+
+        match (self.from, self.to) {
+            (1, ALL_LAYERS) => {
+                let rotation = match move_type {
+                    MoveType::R => 'x',
+                    MoveType::U => 'y',
+                    MoveType::F => 'z',
+                    _ => unreachable!(),
+                };
+                write!(f, "{}{}", rotation, quantifier)
+            }
+            (1, 1) => write!(f, "{:?}{}", move_type, quantifier),
+            (1, 2) => write!(f, "{:?}w{}", move_type, quantifier),
+            (1, to) => write!(f, "{}{:?}w{}", to, move_type, quantifier),
+            (from, to) if from == to => write!(f, "{}{:?}{}", from, move_type, quantifier),
+            (from, to) => write!(f, "{}-{}{:?}w{}", from, to, move_type, quantifier),
+        }
+    }
+}
+
+impl Debug for LayerMove {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        Display::fmt(self, f)
+    }
+}
+
+// an algorithm for a cube of any size
+#[derive(Clone, Eq, PartialEq)]
+pub struct LayerAlgorithm(Vec<LayerMove>);
+
+impl<S: AsRef<str>> From<S> for LayerAlgorithm {
+    fn from(s: S) -> Self {
+        Self(s.as_ref().split_whitespace().map(LayerMove::from).collect())
+    }
+}
+
+// outer layer turns
+impl From<&Algorithm> for LayerAlgorithm {
+    fn from(algorithm: &Algorithm) -> Self {
+        Self(algorithm.into_iter()
+            .map(|m| LayerMove { face: m.face(), from: 1, to: 1, times: m.times() })
+            .collect())
+    }
+}
+
+#[allow(dead_code)]
+impl LayerAlgorithm {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn reversed(&self) -> Self {
+        Self(self.0.iter().rev().map(|m| m.reversed()).collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a LayerAlgorithm {
+    type Item = &'a LayerMove;
+    type IntoIter = <&'a Vec<LayerMove> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Debug for LayerAlgorithm {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.0.iter().join(" "))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cube::Cube;
+use std::fmt::{Display, Formatter, Error};
+use serde::{Deserialize, Serialize, Serializer, Deserializer};
+use serde::de::{self, Visitor};
+
+// layout (most significant bits first, so that the order of packed cubes is the
+// same as the order of cubes):
+// [0]: 8 * 3 bits corner permutation, 8 * 2 bits corner orientation
+// [1]: 12 * 4 bits edge permutation, 12 * 1 bit edge orientation
+// serialized as 32 hex digits, the layout must not change (so supercubes, whose
+// centers don't fit, can't be packed)
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
+pub struct PackedCube([u64; 2]);
+
+fn pack(values: &[u8], bits: u32, mut word: u64) -> u64 {
+    for v in values {
+        word = (word << bits) | *v as u64;
+    }
+    word
+}
+
+fn unpack(values: &mut [u8], bits: u32, mut word: u64) -> u64 {
+    for v in values.iter_mut().rev() {
+        *v = (word & ((1 << bits) - 1)) as u8;
+        word >>= bits;
+    }
+    word
+}
+
+impl From<Cube> for PackedCube {
+    fn from(cube: Cube) -> Self {
+        assert!(!cube.is_supercube(), "supercubes can't be packed\n{:?}", cube);
+        let corners = pack(&cube.co, 2, pack(&cube.cp, 3, 0));
+        let edges = pack(&cube.eo, 1, pack(&cube.ep, 4, 0));
+        Self([corners, edges])
+    }
+}
+
+impl From<PackedCube> for Cube {
+    fn from(packed: PackedCube) -> Self {
+        let mut cube = Cube::solved();
+        unpack(&mut cube.cp, 3, unpack(&mut cube.co, 2, packed.0[0]));
+        unpack(&mut cube.ep, 4, unpack(&mut cube.eo, 1, packed.0[1]));
+        cube
+    }
+}
+
+impl PackedCube {
+    // every piece exactly once and orientations in range (doesn't check solvability)
+    pub fn is_valid(&self) -> bool {
+        let PackedCube([corners, edges]) = *self;
+        if corners >> 40 != 0 || edges >> 60 != 0 { return false }
+
+        let cube = Cube::from(*self);
+        let is_permutation = |p: &[u8]| (0..p.len() as u8).all(|i| p.contains(&i));
+
+        is_permutation(&cube.cp) && is_permutation(&cube.ep) && cube.co.iter().all(|o| *o < 3)
+    }
+}
+
+impl Display for PackedCube {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{:016x}{:016x}", self.0[0], self.0[1])
+    }
+}
+
+impl Serialize for PackedCube {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+        where S: Serializer {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+struct PackedCubeVisitor;
+
+impl<'de> Visitor<'de> for PackedCubeVisitor {
+    type Value = PackedCube;
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "32 hex digits encoding a cube")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: de::Error {
+        let invalid = || E::invalid_value(de::Unexpected::Str(value), &self);
+
+        if value.len() != 32 || !value.is_ascii() { return Err(invalid()) }
+        let word = |s: &str| u64::from_str_radix(s, 16).map_err(|_| invalid());
+        let packed = PackedCube([word(&value[..16])?, word(&value[16..])?]);
+
+        if packed.is_valid() { Ok(packed) } else { Err(invalid()) }
+    }
+}
+
+impl<'de> Deserialize<'de> for PackedCube {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error> where
+        D: Deserializer<'de> {
+        deserializer.deserialize_str(PackedCubeVisitor)
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    pub fn packed(&self) -> PackedCube {
+        PackedCube::from(*self)
+    }
+}
@@ -20,7 +20,7 @@ macro_rules! edge {
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Edge {
-    id: EdgePosition,
+    pub(crate) id: EdgePosition,
     pub(crate) pos: EdgePosition,
 }
 
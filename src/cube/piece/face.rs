@@ -3,6 +3,7 @@ use std::fmt::{Display, Formatter, Error, Debug};
 use std::ops::{Add, Sub};
 use crate::cube::piece::{edge::Edge, corner::Corner};
 use serde::Deserialize;
+use crate::cube::color::ColorScheme;
 
 // TODO: consider converting to an enum
 // TODO: or writing a strong tie between front, back, ... and 0, 1, ...
@@ -64,6 +65,10 @@ impl Face {
         Self(value)
     }
 
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
     pub fn is_even(self) -> bool {
         self.0 % 2 == 0
     }
@@ -114,15 +119,8 @@ impl Transpose for Face {
 
 impl Display for Face {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        write!(f, "{}", match self.0 {
-            0 => "W",
-            1 => "R",
-            2 => "B",
-            3 => "Y",
-            4 => "O",
-            5 => "G",
-            _ => unreachable!(),
-        })
+        // the letters of the western scheme
+        write!(f, "{}", ColorScheme::western().faces[self.index()].letter)
     }
 }
 
@@ -0,0 +1,278 @@
+use crate::cube::{Cube, Corner};
+use crate::cube::algorithm::{Algorithm, Move, MoveType, Metric};
+use crate::cube::coordinate::{permutation_index, permutation_from_index};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::collections::{HashSet, VecDeque};
+
+// the 2x2x2 is the corners of a Cube, turning the whole puzzle doesn't change its
+// state so the corner in slot 6 (3 4 5) is kept in place and only U, R and F are
+// turned, which leaves 7! * 3^6 states
+pub const POCKET_STATES: usize = 3_674_160;
+
+const PERMUTATIONS: usize = 5040; // 7!
+const ORIENTATIONS: usize = 729; // 3^6
+const FIXED_SLOT: usize = 6;
+
+const MOVE_TYPES: [MoveType; 3] = [MoveType::U, MoveType::R, MoveType::F];
+
+// the moves of a metric as indices into MOVES
+fn moves(metric: Metric) -> &'static [usize] {
+    match metric {
+        Metric::Htm => &[0, 1, 2, 3, 4, 5, 6, 7, 8],
+        Metric::Qtm => &[0, 2, 3, 5, 6, 8],
+    }
+}
+
+lazy_static! {
+    static ref MOVES: Vec<Move> = MOVE_TYPES.iter()
+        .flat_map(|t| (1..4).map(move |times| Move::new(*t, times)))
+        .collect();
+
+    // PERMUTATION_TABLE[p][m] is the permutation coordinate after applying MOVES[m]
+    static ref PERMUTATION_TABLE: Vec<[u16; 9]> = move_table(PERMUTATIONS, |p| (p, 0), |(p, _)| p);
+    static ref ORIENTATION_TABLE: Vec<[u16; 9]> = move_table(ORIENTATIONS, |o| (0, o), |(_, o)| o);
+
+    static ref HTM_DISTANCES: Vec<u8> = distances(Metric::Htm);
+    static ref QTM_DISTANCES: Vec<u8> = distances(Metric::Qtm);
+
+    // the 24 rotations of the whole puzzle (as corner permutations) and, for each,
+    // the move which a move of MOVES becomes when conjugated by it
+    static ref ROTATIONS: Vec<(Cube, [Move; 9])> = rotations();
+}
+
+fn corners_only(cube: Cube) -> Cube {
+    Cube { ep: Cube::solved().ep, eo: Cube::solved().eo, ..cube }
+}
+
+// (permutation, orientation) of a cube whose fixed corner is home
+fn encode(cube: &Cube) -> (usize, usize) {
+    let permutation = [0, 1, 2, 3, 4, 5, 7]
+        .iter()
+        .map(|s| if cube.cp[*s] == 7 { 6 } else { cube.cp[*s] })
+        .collect::<Vec<_>>();
+    let orientation = cube.co[..6].iter().fold(0, |acc, o| acc * 3 + *o as usize);
+    (permutation_index(&permutation) as usize, orientation)
+}
+
+fn decode((permutation, mut orientation): (usize, usize)) -> Cube {
+    let mut cube = Cube::solved();
+
+    let mut pieces = [0; 7];
+    permutation_from_index(permutation as u32, &mut pieces);
+    for (s, p) in [0, 1, 2, 3, 4, 5, 7].iter().zip(pieces.iter()) {
+        cube.cp[*s] = if *p == 6 { 7 } else { *p };
+    }
+
+    for o in cube.co[..6].iter_mut().rev() {
+        *o = (orientation % 3) as u8;
+        orientation /= 3;
+    }
+    let sum: u8 = cube.co.iter().sum();
+    cube.co[7] = (3 - sum % 3) % 3;
+    cube
+}
+
+fn move_table(
+    size: usize,
+    decode_coordinate: fn(usize) -> (usize, usize),
+    coordinate: fn((usize, usize)) -> usize,
+) -> Vec<[u16; 9]> {
+    (0..size)
+        .map(|c| {
+            let cube = decode(decode_coordinate(c));
+            let mut row = [0; 9];
+            for (m, r) in MOVES.iter().zip(row.iter_mut()) {
+                let mut next = cube;
+                next.apply_move(m);
+                *r = coordinate(encode(&next)) as u16;
+            }
+            row
+        })
+        .collect()
+}
+
+fn index((permutation, orientation): (usize, usize)) -> usize {
+    permutation * ORIENTATIONS + orientation
+}
+
+fn distances(metric: Metric) -> Vec<u8> {
+    let mut distances = vec![u8::MAX; POCKET_STATES];
+    distances[0] = 0;
+
+    let mut queue = VecDeque::new();
+    queue.push_back((0, 0));
+    while let Some((p, o)) = queue.pop_front() {
+        let distance = distances[index((p, o))];
+        for m in moves(metric) {
+            let next = (PERMUTATION_TABLE[p][*m] as usize, ORIENTATION_TABLE[o][*m] as usize);
+            if distances[index(next)] == u8::MAX {
+                distances[index(next)] = distance + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+fn distance_table(metric: Metric) -> &'static [u8] {
+    match metric {
+        Metric::Htm => &HTM_DISTANCES,
+        Metric::Qtm => &QTM_DISTANCES,
+    }
+}
+
+fn rotations() -> Vec<(Cube, [Move; 9])> {
+    let all_moves = MoveType::ALL.iter()
+        .flat_map(|t| (1..4).map(move |times| Move::new(*t, times)))
+        .collect::<Vec<_>>();
+    let turn = |m: &Move| corners_only(Cube::from_algorithm(&vec![*m].into_iter().collect()));
+
+    // x, y and z turn all the layers
+    let generators = ["R L'", "U D'", "F B'"]
+        .iter()
+        .map(|a| corners_only(Cube::from_algorithm(&Algorithm::from(a))))
+        .collect::<Vec<_>>();
+
+    let mut rotations = vec![Cube::solved()];
+    let mut i = 0;
+    while i < rotations.len() {
+        for g in &generators {
+            let next = rotations[i] * *g;
+            if !rotations.contains(&next) { rotations.push(next) }
+        }
+        i += 1;
+    }
+
+    rotations.into_iter()
+        .map(|r| {
+            let mut conjugated = [MOVES[0]; 9];
+            for (m, c) in MOVES.iter().zip(conjugated.iter_mut()) {
+                let target = r * turn(m) * r.inverse();
+                *c = *all_moves.iter().find(|n| turn(n) == target).unwrap();
+            }
+            (r, conjugated)
+        })
+        .collect()
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PocketCube(Cube);
+
+#[allow(dead_code)]
+impl PocketCube {
+    pub fn solved() -> Self {
+        Self(Cube::solved())
+    }
+
+    // missing corners are left solved, None unless every corner is in one place and
+    // the twists add up to none
+    pub fn from_corners(corners: impl IntoIterator<Item=Corner>) -> Option<Self> {
+        let mut cube = Cube::solved();
+        let mut placed = HashSet::new();
+        for corner in corners {
+            if !placed.insert(corner.pos.sorted()) { return None }
+            cube.set_corner(&corner);
+        }
+        let pieces = cube.cp.iter().collect::<HashSet<_>>();
+        let twist = cube.co.iter().map(|o| *o as usize).sum::<usize>();
+        if pieces.len() != 8 || twist % 3 != 0 { return None }
+        Some(Self(cube))
+    }
+
+    pub fn from_algorithm(algorithm: &Algorithm) -> Self {
+        Self(corners_only(Cube::from_algorithm(algorithm)))
+    }
+
+    pub fn apply(&mut self, algorithm: &Algorithm) {
+        self.0 = corners_only(self.0 * Cube::from_algorithm(algorithm));
+    }
+
+    pub fn iter_corners(&self) -> impl Iterator<Item=Corner> + '_ {
+        self.0.iter_corners()
+    }
+
+    // a random state, every state is equally likely
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        let i = rng.gen_range(0, POCKET_STATES);
+        Self(decode((i / ORIENTATIONS, i % ORIENTATIONS)))
+    }
+
+    // the coordinates of the state turned so that the fixed corner is home and the
+    // rotation which does that
+    fn normalized(&self) -> ((usize, usize), &'static (Cube, [Move; 9])) {
+        let rotation = ROTATIONS.iter()
+            .find(|(r, _)| {
+                let turned = self.0 * *r;
+                turned.cp[FIXED_SLOT] as usize == FIXED_SLOT && turned.co[FIXED_SLOT] == 0
+            })
+            .unwrap();
+        (encode(&(self.0 * rotation.0)), rotation)
+    }
+
+    // solved up to a rotation of the whole puzzle
+    pub fn is_solved(&self) -> bool {
+        self.normalized().0 == (0, 0)
+    }
+
+    pub fn distance(&self, metric: Metric) -> u8 {
+        distance_table(metric)[index(self.normalized().0)]
+    }
+
+    // an optimal solution in the metric, found by always moving closer to solved
+    pub fn solution(&self, metric: Metric) -> Algorithm {
+        let table = distance_table(metric);
+        let ((mut p, mut o), (_, conjugated)) = self.normalized();
+
+        let mut solution = vec![];
+        while table[index((p, o))] > 0 {
+            let distance = table[index((p, o))];
+            let m = *moves(metric).iter()
+                .find(|m| {
+                    let next = (PERMUTATION_TABLE[p][**m] as usize, ORIENTATION_TABLE[o][**m] as usize);
+                    table[index(next)] < distance
+                })
+                .unwrap();
+            p = PERMUTATION_TABLE[p][m] as usize;
+            o = ORIENTATION_TABLE[o][m] as usize;
+            solution.push(conjugated[m]);
+        }
+
+        let solution = solution.into_iter().collect::<Algorithm>();
+        match metric {
+            Metric::Htm => solution,
+            Metric::Qtm => solution.simplified(),
+        }
+    }
+}
+
+impl From<&Cube> for PocketCube {
+    fn from(cube: &Cube) -> Self {
+        Self(corners_only(*cube))
+    }
+}
+
+// random-state 2x2x2 scrambles (optimal htm solutions of random states, reversed)
+pub struct PocketScrambler {
+    rng: StdRng,
+}
+
+#[allow(dead_code)]
+impl PocketScrambler {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn scramble(&mut self) -> Algorithm {
+        PocketCube::random(&mut self.rng).solution(Metric::Htm).reversed()
+    }
+}
+
+impl Iterator for PocketScrambler {
+    type Item = Algorithm;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.scramble())
+    }
+}
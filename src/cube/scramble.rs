@@ -0,0 +1,69 @@
+use crate::cube::algorithm::{Algorithm, Move, MoveType};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use itertools::Itertools;
+
+// random-move scrambles, as opposed to random-state scrambles
+pub struct Scrambler {
+    move_types: Vec<MoveType>,
+    length: usize,
+    rng: StdRng,
+}
+
+#[allow(dead_code)]
+impl Scrambler {
+    pub fn new(length: usize, seed: u64) -> Self {
+        Self::with_moves(&MoveType::ALL, length, seed)
+    }
+
+    // restricted move sets, e.g. <R, U> for 2-gen training
+    pub fn with_moves(move_types: &[MoveType], length: usize, seed: u64) -> Self {
+        let move_types = move_types.iter().copied().unique().collect::<Vec<_>>();
+
+        // with a single axis the constraints below can't be satisfied for long
+        let axes = move_types.iter().map(|t| t.base_move()).unique().count();
+        assert!(
+            axes >= 2 || length <= move_types.len(),
+            "move set {:?} can't produce a scramble of length {}", move_types, length
+        );
+
+        Self { move_types, length, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    fn allowed(&self, previous: &[Move], move_type: MoveType) -> bool {
+        match previous {
+            [] => true,
+            [.., last] if last.move_type() == move_type => false,
+            // disallow sequences like R L R
+            [.., second_last, last] =>
+                !(second_last.base_move() == last.base_move()
+                    && last.base_move() == move_type.base_move()),
+            _ => true,
+        }
+    }
+
+    pub fn scramble(&mut self) -> Algorithm {
+        let mut moves = Vec::with_capacity(self.length);
+
+        while moves.len() < self.length {
+            let candidates = self.move_types
+                .iter()
+                .copied()
+                .filter(|t| self.allowed(&moves, *t))
+                .collect::<Vec<_>>();
+
+            let move_type = candidates[self.rng.gen_range(0, candidates.len())];
+            moves.push(Move::new(move_type, self.rng.gen_range(1, 4)));
+        }
+
+        moves.into_iter().collect()
+    }
+}
+
+impl Iterator for Scrambler {
+    type Item = Algorithm;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.scramble())
+    }
+}
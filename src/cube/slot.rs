@@ -0,0 +1,109 @@
+use crate::cube::{Edge, Corner};
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::{EdgePosition, CornerPosition};
+
+// slots in the order in which Cube stores its pieces
+// the first face of each slot is its reference face for orientation (U/D, F/B for
+// the E-slice edges), corner faces are listed clockwise
+const EDGE_SLOTS: [(u8, u8); 12] = [
+    (0, 1), (0, 2), (0, 4), (0, 5),
+    (3, 1), (3, 2), (3, 4), (3, 5),
+    (1, 2), (4, 2), (4, 5), (1, 5),
+];
+
+const CORNER_SLOTS: [(u8, u8, u8); 8] = [
+    (0, 2, 1), (0, 4, 2), (0, 5, 4), (0, 1, 5),
+    (3, 1, 2), (3, 2, 4), (3, 4, 5), (3, 5, 1),
+];
+
+// the E-slice edges are the last 4
+pub(crate) const FIRST_SLICE_EDGE: u8 = 8;
+
+// slot indices by the bit mask of the faces of the slot (order independent)
+const fn edge_index_table() -> [u8; 64] {
+    let mut table = [u8::MAX; 64];
+    let mut i = 0;
+    while i < EDGE_SLOTS.len() {
+        let (f0, f1) = EDGE_SLOTS[i];
+        table[(1 << f0) | (1 << f1)] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+const fn corner_index_table() -> [u8; 64] {
+    let mut table = [u8::MAX; 64];
+    let mut i = 0;
+    while i < CORNER_SLOTS.len() {
+        let (f0, f1, f2) = CORNER_SLOTS[i];
+        table[(1 << f0) | (1 << f1) | (1 << f2)] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+const EDGE_INDICES: [u8; 64] = edge_index_table();
+const CORNER_INDICES: [u8; 64] = corner_index_table();
+
+pub(crate) fn edge_slot(index: usize) -> EdgePosition {
+    let (f0, f1) = EDGE_SLOTS[index];
+    pos!(f0, f1)
+}
+
+pub(crate) fn corner_slot(index: usize) -> CornerPosition {
+    let (f0, f1, f2) = CORNER_SLOTS[index];
+    pos!(f0, f1, f2)
+}
+
+pub(crate) fn edge_index(position: EdgePosition) -> usize {
+    let EdgePosition(f0, f1) = position;
+    match EDGE_INDICES[(1 << f0.index()) | (1 << f1.index())] {
+        u8::MAX => panic!("invalid edge position {:?}", position),
+        i => i as usize,
+    }
+}
+
+pub(crate) fn corner_index(position: CornerPosition) -> usize {
+    let CornerPosition(f0, f1, f2) = position;
+    match CORNER_INDICES[(1 << f0.index()) | (1 << f1.index()) | (1 << f2.index())] {
+        u8::MAX => panic!("invalid corner position {:?}", position),
+        i => i as usize,
+    }
+}
+
+fn is_up_or_down(face: Face) -> bool {
+    face == Face::new(0) || face == Face::new(3)
+}
+
+// the edge (piece) in a slot with a given orientation
+pub(crate) fn make_edge(slot: usize, piece: u8, orientation: u8) -> Edge {
+    let EdgePosition(f0, f1) = edge_slot(slot);
+    let pos = if orientation == 0 { pos!(f0, f1) } else { pos!(f1, f0) };
+    Edge::new(edge_slot(piece as usize), pos)
+}
+
+pub(crate) fn make_corner(slot: usize, piece: u8, orientation: u8) -> Corner {
+    let CornerPosition(f0, f1, f2) = corner_slot(slot);
+    let mut faces = [f0, f1, f2];
+    faces.rotate_left(orientation as usize);
+    Corner::new(corner_slot(piece as usize), pos!(faces[0], faces[1], faces[2]))
+}
+
+// (slot, piece, orientation), the inverse of make_edge
+pub(crate) fn locate_edge(edge: &Edge) -> (usize, u8, u8) {
+    let slot = edge_index(edge.pos);
+    let piece = edge_index(edge.id);
+    let orientation = edge.id_on(edge_slot(slot).0) != edge_slot(piece).0;
+    (slot, piece as u8, orientation as u8)
+}
+
+pub(crate) fn locate_corner(corner: &Corner) -> (usize, u8, u8) {
+    let slot = corner_index(corner.pos);
+    let piece = corner_index(corner.id);
+    let CornerPosition(f0, f1, f2) = corner_slot(slot);
+    let orientation = [f0, f1, f2]
+        .iter()
+        .position(|f| is_up_or_down(corner.id_on(*f)))
+        .unwrap();
+    (slot, piece as u8, orientation as u8)
+}
@@ -1,15 +1,209 @@
-use crate::cube::Cube;
+use crate::cube::{Cube, Edge, Corner};
 use crate::cube::algorithm::Algorithm;
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::{EdgePosition, CornerPosition};
+use crate::cube::symmetry::Symmetry;
+use crate::cube::transpose::Transposed;
+use crate::cube::f2l::{FRONTS, SlotStatus};
+use crate::cube::last_layer::{Step, DATA_FRAME};
+use crate::algorithm_data::{cross_data, f2l_data};
+use crate::support::Tern;
+use std::fmt::{Display, Formatter, Error};
+
+// cfop with the algorithms of the data files: the cross on face 0, the four slots
+// around it, then the last layer; cross.txt and f2l.txt are written for one piece
+// (or pair) and the other pieces of the step which are already solved, the cube is
+// turned so that the piece to solve is that one (see f2l.rs)
+
+pub(crate) fn is_edge_solved(cube: &Cube, position: EdgePosition) -> bool {
+    cube.edge_at(position) == Edge::new(position, position)
+}
+
+pub(crate) fn is_corner_solved(cube: &Cube, position: CornerPosition) -> bool {
+    cube.corner_at(position) == Corner::new(position, position)
+}
+
+// the algorithm of the conditions first, then the others, in the data's frame
+fn candidates<K: PartialEq>(tern: &Tern<Vec<K>, Algorithm>, solved: &Vec<K>, front: Face) -> Vec<Algorithm> {
+    let rotation = Symmetry::rotation(cpos!(1, 3), cpos!(front, 3));
+    let first = tern.eval(solved);
+    Some(first).into_iter()
+        .chain(tern.results().into_iter().filter(|alg| *alg != first))
+        .map(|alg| alg.symmetric(&DATA_FRAME).symmetric(&rotation))
+        .collect()
+}
+
+// the cross.txt algorithms for the edge of a slot, the conditions are the other
+// cross edges which are solved
+fn cross_step(cube: &Cube, front: Face) -> Vec<Algorithm> {
+    let normalized = cube.transposed(cpos!(front, 3), cpos!(1, 3));
+    if is_edge_solved(&normalized, pos!(0, 5)) { return vec![] }
+
+    let key = normalized.iter_edges().find(|e| e.id == pos!(0, 5)).unwrap().pos;
+    let solved = [pos!(0, 1), pos!(0, 2), pos!(0, 4)]
+        .iter()
+        .copied()
+        .filter(|p| is_edge_solved(&normalized, *p))
+        .collect::<Vec<_>>();
+    candidates(&cross_data()[&key], &solved, front)
+}
+
+fn f2l_solved(cube: &Cube) -> [bool; 4] {
+    let cross = cube.is_cross_solved(Face::new(0));
+    FRONTS.map(|f| cross && cube.f2l_slot(Face::new(f)).is_solved())
+}
+
+// the f2l.txt algorithms for a slot, the conditions are the other solved slots
+fn f2l_step(cube: &Cube, front: Face) -> Vec<Algorithm> {
+    let key = match cube.f2l_slot(front).status {
+        SlotStatus::Solved => return vec![],
+        SlotStatus::Unsolved(key) => key,
+    };
+
+    let normalized = cube.transposed(cpos!(front, 3), cpos!(1, 3));
+    let solved = [(pos!(0, 1, 2), pos!(1, 2)), (pos!(0, 1, 5), pos!(1, 5)), (pos!(0, 2, 4), pos!(2, 4))]
+        .iter()
+        .copied()
+        .filter(|(c, e)| is_corner_solved(&normalized, *c) && is_edge_solved(&normalized, *e))
+        .collect::<Vec<_>>();
+    candidates(&f2l_data()[&key], &solved, front)
+}
+
+// solves the pieces (or pairs) of a step one by one until goal of the four are solved,
+// every algorithm has to keep what's already solved, solved(cube)[i] is whether the
+// i-th one is solved; None when no algorithm makes progress
+fn solve_step(
+    cube: &mut Cube,
+    step: fn(&Cube, Face) -> Vec<Algorithm>,
+    solved: impl Fn(&Cube) -> [bool; 4],
+    goal: usize,
+) -> Option<Algorithm> {
+    let mut solution = Algorithm::from("");
+    while solved(cube).iter().filter(|s| **s).count() < goal {
+        let before = solved(cube);
+        let progress = |after: [bool; 4]| after != before
+            && before.iter().zip(after.iter()).all(|(b, a)| !b || *a);
+
+        let algorithm = FRONTS.iter()
+            .flat_map(|front| step(cube, Face::new(*front)))
+            .find(|alg| progress(solved(&(*cube * Cube::from_algorithm(alg)))))?;
+        cube.apply(&algorithm);
+        solution = solution + algorithm;
+    }
+    Some(solution)
+}
+
+// where the algorithms of the data fall short, with the cube as it is then
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub enum SolveError {
+    // no algorithm of cross.json or f2l.json (the name of the step) gets further
+    NoProgress(&'static str, Cube),
+    // the case of the last layer (or the last slot) has no algorithm
+    Unrecognized(Step, Cube),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SolveError::NoProgress(step, cube) => write!(f, "no algorithm makes progress on the {} of\n{:?}", step, cube),
+            SolveError::Unrecognized(step, cube) => write!(f, "unrecognized {:?} case of\n{:?}", step, cube),
+        }
+    }
+}
+
+// the stages which the solver can use besides those of cfop, each when its case comes up
+#[allow(dead_code)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Stages {
+    // winter variation and vls take over the last pair when its f2l algorithm gets it
+    // where they start
+    pub wv: bool,
+    pub vls: bool,
+    // coll (then pll) and zbll replace oll and pll when the edges are oriented
+    pub coll: bool,
+    pub zbll: bool,
+}
 
 impl Cube {
-    fn solve_cross_alg(&self) -> Algorithm {
-        unimplemented!()
+    fn solve_cross_alg(&self) -> Option<Algorithm> {
+        let solved = |cube: &Cube| FRONTS.map(|f| is_edge_solved(cube, pos!(0, f)));
+        solve_step(&mut self.clone(), cross_step, solved, 4)
+    }
+
+    // the cross has to be solved, this stops once slots of the four are solved
+    fn solve_f2l_alg(&self, slots: usize) -> Option<Algorithm> {
+        solve_step(&mut self.clone(), f2l_step, f2l_solved, slots)
+    }
+
+    // the last pair (the others being solved) with winter variation or vls where a
+    // prefix of one of its f2l algorithms leads to a case of them, with the first of its
+    // f2l algorithms otherwise, None when none solves it
+    fn solve_last_slot_alg(&self, stages: &Stages) -> Option<Algorithm> {
+        let front = match FRONTS.iter().map(|f| Face::new(*f)).find(|f| !self.f2l_slot(*f).is_solved()) {
+            Some(front) => front,
+            None => return Some(Algorithm::from("")),
+        };
+        let algorithms = f2l_step(self, front).into_iter()
+            .filter(|alg| f2l_solved(&(*self * Cube::from_algorithm(alg))) == [true; 4])
+            .collect::<Vec<_>>();
+        let steps = [(stages.wv, Step::Wv), (stages.vls, Step::Vls)];
+        for alg in &algorithms {
+            for length in 0..=alg.len() {
+                let prefix = alg.clone().into_iter().take(length).collect::<Algorithm>();
+                let cube = *self * Cube::from_algorithm(&prefix);
+                let case = steps.iter()
+                    .filter(|(enabled, _)| *enabled)
+                    .find_map(|(_, step)| cube.last_layer_case(*step));
+                if let Some(case) = case {
+                    return Some(prefix + case.algorithm);
+                }
+            }
+        }
+        algorithms.into_iter().next()
+    }
+
+    // a solution with the cross on face 0, supercubes get their centers solved too
+    #[allow(dead_code)]
+    pub fn solution(&self) -> Result<Algorithm, SolveError> {
+        self.solution_with(&Stages::default())
     }
 
+    // the same with some more stages
     #[allow(dead_code)]
-    pub fn solution(&self) -> Algorithm {
-        let cross_alg = self.solve_cross_alg();
+    pub fn solution_with(&self, stages: &Stages) -> Result<Algorithm, SolveError> {
+        let mut cube = *self;
+        let mut solution = Algorithm::from("");
+        let mut step = |cube: &mut Cube, algorithm: Algorithm| {
+            cube.apply(&algorithm);
+            solution = solution.clone() + algorithm;
+        };
+
+        let cross = cube.solve_cross_alg().ok_or(SolveError::NoProgress("cross", cube))?;
+        step(&mut cube, cross);
+        let last_slot = stages.wv || stages.vls;
+        let f2l = cube.solve_f2l_alg(if last_slot { 3 } else { 4 }).ok_or(SolveError::NoProgress("f2l", cube))?;
+        step(&mut cube, f2l);
+        if last_slot {
+            let pair = cube.solve_last_slot_alg(stages).ok_or(SolveError::NoProgress("f2l", cube))?;
+            step(&mut cube, pair);
+        }
+
+        let case = |cube: &Cube, enabled: bool, last_layer: Step| if enabled { cube.last_layer_case(last_layer) } else { None };
+        if let Some(zbll) = case(&cube, stages.zbll, Step::Zbll) {
+            step(&mut cube, zbll.algorithm);
+        } else {
+            let oll = case(&cube, stages.coll, Step::Coll)
+                .or_else(|| cube.oll_case())
+                .ok_or(SolveError::Unrecognized(Step::Oll, cube))?;
+            step(&mut cube, oll.algorithm);
+            let pll = cube.pll_case().ok_or(SolveError::Unrecognized(Step::Pll, cube))?.algorithm;
+            step(&mut cube, pll);
+        }
+        // nothing to do unless it's a supercube
+        let centers = cube.center_solution();
+        step(&mut cube, centers);
 
-        unimplemented!()
+        Ok(solution.simplified())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,67 @@
+use crate::cube::Cube;
+use crate::cube::algorithm::Algorithm;
+use crate::cube::piece::face::Face;
+use crate::cube::symmetry::Symmetry;
+use itertools::Itertools;
+
+// a supercube (or a picture cube) also shows how its centers are turned, a face turn
+// turns its center along with it; once the pieces are solved the quarter turns of
+// the centers add up to an even number (a quarter turn is an odd permutation of the
+// corners) so they can always be solved with the two algorithms below
+
+lazy_static! {
+    // the centers of faces 0 and 1 a quarter turn clockwise, the pieces stay
+    static ref QUARTER_TURNS: Algorithm = Algorithm::from("U2 F2 U' F2 U2 F' ".repeat(3));
+    // the center of face 0 a half turn, the pieces stay
+    static ref HALF_TURN: Algorithm = Algorithm::from("R L U2 R' L' U ".repeat(2));
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // the same state, tracking the centers from now on (they count as solved), None when
+    // the corners are oddly permuted: then a center is turned a quarter
+    pub fn with_centers(&self) -> Option<Self> {
+        let parity = self.cp.iter().tuple_combinations().filter(|(a, b)| a > b).count() % 2;
+        if parity == 1 { return None }
+        Some(Self { centers: Some([0; 6]), ..*self })
+    }
+
+    pub fn is_supercube(&self) -> bool {
+        self.centers.is_some()
+    }
+
+    // clockwise quarter turns of the center of a face, None if centers aren't tracked
+    pub fn center_orientation(&self, face: Face) -> Option<u8> {
+        self.centers.map(|c| c[face.index()])
+    }
+
+    // an algorithm which turns the centers back without moving any piece, the pieces
+    // have to be solved
+    pub fn center_solution(&self) -> Algorithm {
+        assert!(Self { centers: None, ..*self }.is_solved(), "pieces aren't solved\n{:?}", self);
+        let mut centers = self.centers.unwrap_or([0; 6]);
+        assert_eq!(centers.iter().sum::<u8>() % 2, 0, "centers can't be turned so\n{:?}", self);
+        let mut solution = Algorithm::from("");
+
+        // odd centers are turned together with the next face (which is adjacent), that
+        // leaves face 5 even too
+        for face in 0..5 {
+            if centers[face] % 2 == 1 {
+                let rotation = Symmetry::rotation_to(Face::new(face as u8), Face::new(face as u8 + 1));
+                solution = solution + QUARTER_TURNS.symmetric(&rotation);
+                centers[face] = (centers[face] + 1) % 4;
+                centers[face + 1] = (centers[face + 1] + 1) % 4;
+            }
+        }
+
+        for (face, turns) in centers.iter().enumerate() {
+            if *turns == 2 {
+                let face = Face::new(face as u8);
+                let rotation = Symmetry::rotation_to(face, face.adjacent_clockwise()[0]);
+                solution = solution + HALF_TURN.symmetric(&rotation);
+            }
+        }
+
+        solution.simplified()
+    }
+}
@@ -0,0 +1,74 @@
+use crate::cube::{Cube, FaceMatrix};
+use crate::cube::piece::position::CubePosition;
+use crate::cube::color::ColorScheme;
+use std::fmt::Write;
+
+// where every face of the net is (in faces, column and row) and how it's seen
+// (front, down), as in write_net
+pub(crate) const NET: [((u8, u8), u32, u32); 6] = [
+    ((0, 1), 1, 0),
+    ((5, 3), 0, 1),
+    ((1, 3), 1, 1),
+    ((2, 3), 2, 1),
+    ((4, 3), 3, 1),
+    ((3, 4), 1, 2),
+];
+
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    // in pixels, faces are separated by a quarter of it
+    pub sticker_size: u32,
+    pub scheme: ColorScheme,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            sticker_size: 30,
+            scheme: ColorScheme::default(),
+        }
+    }
+}
+
+// the net of a cube, face(position) is the face seen from the position
+pub(crate) fn net_svg(face: impl Fn(CubePosition) -> FaceMatrix, options: &SvgOptions) -> String {
+    let size = options.sticker_size;
+    let gap = size / 4;
+    let face_size = 3 * size + gap;
+    let (width, height) = (4 * face_size + gap, 3 * face_size + gap);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        width, height,
+    ).unwrap();
+
+    for ((front, down), column, row) in NET.iter() {
+        let (x, y) = (gap + column * face_size, gap + row * face_size);
+        for (i, line) in face(cpos!(*front, *down)).0.iter().enumerate() {
+            for (j, sticker) in line.iter().enumerate() {
+                writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{3}" height="{3}" fill="{}" stroke="#000000" stroke-width="1"/>"##,
+                    x + j as u32 * size, y + i as u32 * size, options.scheme.color(Some(*sticker)).hex(), size,
+                ).unwrap();
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // the net printed by Display as an svg image
+    pub fn to_svg(self) -> String {
+        self.to_svg_with(&SvgOptions::default())
+    }
+
+    pub fn to_svg_with(self, options: &SvgOptions) -> String {
+        net_svg(|position| self.get_face_matrix(position), options)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::cube::Cube;
+use crate::cube::algorithm::{Algorithm, Move, MoveType};
+use crate::cube::piece::face::Face;
+use crate::cube::piece::position::CubePosition;
+use crate::cube::transpose::{Transposed, Projection};
+use std::fmt::{Debug, Formatter, Error};
+use std::ops::Mul;
+
+// same axes as the face numbering: 0 up, 1 front, 2 right (3, 4, 5 opposite)
+pub(crate) const FACE_VECTORS: [[i8; 3]; 6] = [
+    [0, 1, 0], [0, 0, 1], [1, 0, 0],
+    [0, -1, 0], [0, 0, -1], [-1, 0, 0],
+];
+
+const IDENTITY: Projection = [
+    Face::new(0), Face::new(1), Face::new(2),
+    Face::new(3), Face::new(4), Face::new(5),
+];
+
+lazy_static! {
+    static ref SYMMETRIES: Vec<Symmetry> = {
+        let mut symmetries = vec![];
+        for up in 0..6 {
+            for front in (0..6).filter(|f| *f % 3 != up % 3) {
+                for right in (0..6).filter(|f| *f % 3 != up % 3 && *f % 3 != front % 3) {
+                    let (up, front, right) = (Face::new(up), Face::new(front), Face::new(right));
+                    symmetries.push(Symmetry([
+                        up, front, right,
+                        up.opposite(), front.opposite(), right.opposite(),
+                    ]));
+                }
+            }
+        }
+        symmetries
+    };
+}
+
+// a permutation of the faces which keeps opposite faces opposite (rotations and
+// reflections of the whole cube), symmetry.0[f] is the image of face f
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Symmetry(Projection);
+
+#[allow(dead_code)]
+impl Symmetry {
+    // all 48, the identity first
+    pub fn all() -> &'static [Symmetry] {
+        &SYMMETRIES
+    }
+
+    pub fn identity() -> Self {
+        Symmetry(IDENTITY)
+    }
+
+    // the rotation used by Transpose
+    pub fn rotation(from: CubePosition, to: CubePosition) -> Self {
+        Symmetry(IDENTITY.map(|f| f.transposed(from, to)))
+    }
+
+    // the symmetry used by Transpose, any projections
+    pub(crate) fn between(from: Projection, to: Projection) -> Self {
+        Symmetry(IDENTITY.map(|f| f.transposed_with_projection(from, to)))
+    }
+
+    // the rotation taking face 0 to up and face 1 to front
+    pub fn rotation_to(up: Face, front: Face) -> Self {
+        *Self::all()
+            .iter()
+            .find(|s| !s.is_reflection() && s.image(Face::new(0)) == up && s.image(Face::new(1)) == front)
+            .unwrap_or_else(|| panic!("no rotation takes (0, 1) to ({}, {})", up, front))
+    }
+
+    pub fn image(&self, face: Face) -> Face {
+        self.0[face.index()]
+    }
+
+    pub fn is_reflection(&self) -> bool {
+        let [u, f, r] = [0, 1, 2].map(|i| FACE_VECTORS[self.0[i].index()]);
+        let det = u[0] * (f[1] * r[2] - f[2] * r[1])
+            - u[1] * (f[0] * r[2] - f[2] * r[0])
+            + u[2] * (f[0] * r[1] - f[1] * r[0]);
+        // the identity has det(up, front, right) == 1
+        det == -1
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut inverse = IDENTITY;
+        for (face, image) in self.0.iter().enumerate() {
+            inverse[image.index()] = Face::new(face as u8);
+        }
+        Symmetry(inverse)
+    }
+}
+
+// a * b applies a, then b
+impl Mul for Symmetry {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Symmetry(self.0.map(|f| other.image(f)))
+    }
+}
+
+impl Debug for Symmetry {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "Sym{:?}", self.0)
+    }
+}
+
+// symmetries act on both the positions and the colors of the pieces
+#[allow(dead_code)]
+impl Cube {
+    pub fn symmetric(&self, symmetry: &Symmetry) -> Self {
+        self.transposed_with_projection(IDENTITY, symmetry.0)
+    }
+
+    // the smallest of the (up to) 48 symmetric states and a symmetry which leads to it
+    pub fn symmetry_canonical(&self) -> (Self, Symmetry) {
+        Symmetry::all()
+            .iter()
+            .map(|s| (self.symmetric(s), *s))
+            .min_by_key(|(cube, _)| *cube)
+            .unwrap()
+    }
+}
+
+#[allow(dead_code)]
+impl Algorithm {
+    // reflections turn every move the other way
+    pub fn symmetric(&self, symmetry: &Symmetry) -> Self {
+        self.into_iter()
+            .map(|m| {
+                let move_type = MoveType::from(symmetry.image(m.face()));
+                let times = if symmetry.is_reflection() { 4 - m.times() } else { m.times() };
+                Move::new(move_type, times)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,278 @@
+use crate::cube::{Cube, surrounding};
+use crate::cube::algorithm::MoveType;
+use crate::cube::piece::face::Face;
+use crate::cube::last_layer::{Step, DATA_FRAME, LAST_LAYER};
+use crate::cube::svg::SvgOptions;
+use crate::cube::color::ColorScheme;
+use itertools::Itertools;
+use std::fmt::Write;
+
+// (row, column) in the 5 x 5 grid of a top view: the last layer is rows and columns
+// 1 to 3, the side stickers of the layer are around it and the corners are empty
+type Cell = (usize, usize);
+
+const ARROW_MARKER: &str = r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="4" markerHeight="4" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z"/></marker></defs>"#;
+
+// the faces below, left of, above and right of the last layer, seen from above with
+// the front of the data below
+fn around() -> [Face; 4] {
+    surrounding(cpos!(LAST_LAYER, DATA_FRAME.image(Face::new(1))))
+}
+
+// the faces of the piece of a last layer cell, the last layer first
+fn piece_faces((row, column): Cell) -> Vec<Face> {
+    let [d, l, u, r] = around();
+    let mut faces = vec![LAST_LAYER];
+    match row { 1 => faces.push(u), 3 => faces.push(d), _ => () }
+    match column { 1 => faces.push(l), 3 => faces.push(r), _ => () }
+    faces
+}
+
+// the faces of the piece a cell shows a sticker of, the face of the sticker first,
+// None for the corners of the grid
+fn sticker_faces((row, column): Cell) -> Option<Vec<Face>> {
+    let [d, l, u, r] = around();
+    let inner = |i: usize| i.clamp(1, 3);
+    let mut faces = piece_faces((inner(row), inner(column)));
+    let side = match (row, column) {
+        (0, 0) | (0, 4) | (4, 0) | (4, 4) => return None,
+        (0, _) => u,
+        (4, _) => d,
+        (_, 0) => l,
+        (_, 4) => r,
+        _ => return Some(faces),
+    };
+    faces.retain(|f| *f != side);
+    faces.insert(0, side);
+    Some(faces)
+}
+
+// the name of a piece in the letters of the data (the last layer is U)
+fn piece_name(cell: Cell) -> String {
+    let inverse = DATA_FRAME.inverse();
+    piece_faces(cell).iter()
+        .map(|f| format!("{:?}", MoveType::from(inverse.image(*f))))
+        .join("")
+}
+
+// a quarter turn of the grid
+fn turned((row, column): Cell) -> Cell {
+    (column, 4 - row)
+}
+
+// the last layer seen from above as in the data, stickers which don't matter for the
+// step are None
+pub struct TopView {
+    cells: [[Option<Face>; 5]; 5],
+    // (from, to) of every piece which has to move (pll only)
+    arrows: Vec<(Cell, Cell)>,
+}
+
+#[allow(dead_code)]
+impl TopView {
+    // the arrows in cycles, named like "UF → UR → UB → UF"
+    pub fn cycles(&self) -> Vec<String> {
+        let mut cycles = vec![];
+        let mut visited = vec![];
+        for (start, _) in &self.arrows {
+            if visited.contains(start) { continue }
+            let mut cycle = vec![*start];
+            let mut cell = *start;
+            while let Some((_, to)) = self.arrows.iter().find(|(from, _)| *from == cell) {
+                cycle.push(*to);
+                if *to == *start { break }
+                cell = *to;
+            }
+            visited.extend(cycle.iter().copied());
+            cycles.push(cycle.into_iter().map(piece_name).join(" → "));
+        }
+        cycles
+    }
+
+    // the colors of the scheme as colored squares, unknown stickers are black
+    pub fn to_unicode(&self, scheme: &ColorScheme) -> String {
+        let mut text = self.cells.iter()
+            .enumerate()
+            .map(|(row, line)| (0..5)
+                .map(|column| match (sticker_faces((row, column)), line[column]) {
+                    (None, _) => "  ",
+                    (_, Some(face)) => scheme.color(Some(face)).emoji(),
+                    (_, None) => "⬛",
+                })
+                .join(""))
+            .join("\n");
+        for cycle in self.cycles() {
+            write!(text, "\n{}", cycle).unwrap();
+        }
+        text
+    }
+
+    // width and height of the diagram
+    fn svg_size(options: &SvgOptions) -> u32 {
+        let size = options.sticker_size;
+        2 * (size / 4) + 2 * (size / 3) + 2 * (size / 10).max(1) + 3 * size
+    }
+
+    // where a row or column starts and how long it is
+    fn svg_span(i: usize, options: &SvgOptions) -> (u32, u32) {
+        let size = options.sticker_size;
+        let (margin, side, gap) = (size / 4, size / 3, (size / 10).max(1));
+        match i {
+            0 => (margin, side),
+            4 => (margin + side + 2 * gap + 3 * size, side),
+            _ => (margin + side + gap + (i as u32 - 1) * size, size),
+        }
+    }
+
+    // the diagram without the svg element, at (x, y)
+    fn write_svg_body(&self, svg: &mut String, options: &SvgOptions, x: u32, y: u32) {
+        for (row, line) in self.cells.iter().enumerate() {
+            for (column, sticker) in line.iter().enumerate() {
+                if sticker_faces((row, column)).is_none() { continue }
+                let ((top, height), (left, width)) = (Self::svg_span(row, options), Self::svg_span(column, options));
+                let color = options.scheme.color(*sticker).hex();
+                writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#000000" stroke-width="1"/>"##,
+                    x + left, y + top, width, height, color,
+                ).unwrap();
+            }
+        }
+
+        let size = options.sticker_size as f64;
+        let center = |i: usize| {
+            let (start, length) = Self::svg_span(i, options);
+            start as f64 + length as f64 / 2.
+        };
+        for (from, to) in &self.arrows {
+            // a swap is a single arrow with two heads
+            let swap = self.arrows.contains(&(*to, *from));
+            if swap && from > to { continue }
+
+            let (x1, y1) = (x as f64 + center(from.1), y as f64 + center(from.0));
+            let (x2, y2) = (x as f64 + center(to.1), y as f64 + center(to.0));
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            let (dx, dy) = ((x2 - x1) / length * size / 4., (y2 - y1) / length * size / 4.);
+            writeln!(
+                svg,
+                r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#000000" stroke-width="{:.1}"{} marker-end="url(#arrow)"/>"##,
+                x1 + dx, y1 + dy, x2 - dx, y2 - dy, (size / 12.).max(1.),
+                if swap { r#" marker-start="url(#arrow)""# } else { "" },
+            ).unwrap();
+        }
+    }
+
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let size = Self::svg_size(options);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">\n{1}\n",
+            size, ARROW_MARKER,
+        );
+        self.write_svg_body(&mut svg, options, 0, 0);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[allow(dead_code)]
+impl Cube {
+    // the last layer (face 3) seen from above, for oll (and the last slot) only its color
+    // is shown, for pll (coll and zbll) every color and an arrow for every piece which
+    // the case moves (the layer is turned so that as few pieces as possible move)
+    pub fn top_view(&self, step: Step) -> TopView {
+        let permutation = matches!(step, Step::Pll | Step::Coll | Step::Zbll);
+        let mut cells = [[None; 5]; 5];
+        for (row, line) in cells.iter_mut().enumerate() {
+            for (column, cell) in line.iter_mut().enumerate() {
+                *cell = sticker_faces((row, column))
+                    .map(|faces| self.sticker(&faces))
+                    .filter(|color| permutation || *color == LAST_LAYER);
+            }
+        }
+
+        let arrows = if permutation { self.last_layer_arrows() } else { vec![] };
+        TopView { cells, arrows }
+    }
+
+    fn last_layer_arrows(&self) -> Vec<(Cell, Cell)> {
+        let inner = (1..4)
+            .flat_map(|row| (1..4).map(move |column| (row, column)))
+            .filter(|cell| *cell != (2, 2))
+            .collect::<Vec<_>>();
+
+        // the cell every piece belongs in (pieces of other layers have none)
+        let homes = inner.iter()
+            .filter_map(|cell| {
+                let faces = piece_faces(*cell);
+                let colors = (0..faces.len())
+                    .map(|i| {
+                        let mut faces = faces.clone();
+                        faces.swap(0, i);
+                        self.sticker(&faces)
+                    })
+                    .sorted()
+                    .collect::<Vec<_>>();
+                let home = inner.iter().find(|c| piece_faces(**c).into_iter().sorted().eq(colors.iter().copied()))?;
+                Some((*cell, *home))
+            })
+            .collect::<Vec<_>>();
+
+        (0..4)
+            .map(|turns| homes.iter()
+                .map(|(cell, home)| (*cell, (0..turns).fold(*home, |c, _| turned(c))))
+                .filter(|(cell, home)| cell != home)
+                .collect::<Vec<_>>())
+            .min_by_key(|arrows| arrows.len())
+            .unwrap()
+    }
+}
+
+// every case of the data (but the skip) in the usual order, with its algorithm as
+// written in the data
+fn sheet_cases(step: Step) -> Vec<(&'static str, String, TopView)> {
+    step.data()
+        .iter()
+        .filter(|(_, alg)| !alg.is_empty())
+        .sorted_by_key(|(key, _)| step.order(key))
+        .map(|(key, alg)| {
+            let view = Cube::from_last_layer_algorithm(alg).top_view(step);
+            (step.name(key), format!("{:?}", alg), view)
+        })
+        .collect()
+}
+
+// a sheet of every case of the data of a step, in two columns
+pub fn sheet_svg(step: Step, options: &SvgOptions) -> String {
+    let cases = sheet_cases(step);
+    let size = options.sticker_size;
+    let diagram = TopView::svg_size(options);
+    let (column_width, row_height) = (diagram + 14 * size, diagram + size / 2);
+    let rows = (cases.len() as u32).div_ceil(2);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n{2}\n",
+        2 * column_width, rows * row_height, ARROW_MARKER,
+    );
+    for (i, (name, alg, view)) in cases.iter().enumerate() {
+        let (x, y) = (i as u32 % 2 * column_width, i as u32 / 2 * row_height);
+        view.write_svg_body(&mut svg, options, x, y);
+        writeln!(
+            svg,
+            r#"<text x="{0}" y="{1}" font-family="sans-serif" font-size="{2}" font-weight="bold">{3}</text>"#,
+            x + diagram + size / 4, y + size, size * 2 / 3, name,
+        ).unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{0}" y="{1}" font-family="sans-serif" font-size="{2}">{3}</text>"#,
+            x + diagram + size / 4, y + 2 * size, size / 2, alg,
+        ).unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn sheet_unicode(step: Step, scheme: &ColorScheme) -> String {
+    sheet_cases(step).iter()
+        .map(|(name, alg, view)| format!("{}: {}\n{}\n", name, alg, view.to_unicode(scheme)))
+        .join("\n")
+}
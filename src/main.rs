@@ -10,6 +10,7 @@
 
 #[allow(unused_imports)] use std::fs::File;
 #[allow(unused_imports)] use std::io::Read;
+#[allow(unused_imports)] use std::path::Path;
 #[allow(unused_imports)] //use algorithm_data::{load_cross, load_f2l, load2};
 #[allow(unused_imports)] use std::hash::Hash;
 #[allow(unused_imports)] use itertools::Itertools;
@@ -19,6 +20,7 @@
 #[allow(unused_imports)] use crate::algorithm_data::{cross_data, f2l_data, oll_data, pll_data};
 
 extern crate md5;
+#[macro_use] extern crate lazy_static;
 
 #[macro_use]
 mod support;
@@ -38,6 +40,94 @@ fn main() {
 //        .expect("failed to open file")
 //        .read_to_string(&mut s);
 
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    // a broken override file is reported before any data is loaded
+    if let Err(e) = algorithm_data::set_overrides_from_var() {
+        eprintln!("{}", e);
+        exit(1);
+    }
+    match args.first().map(String::as_str) {
+        // migrates the .txt data files of a directory to json
+        Some("convert") => {
+            let dir = args.get(1).map_or("src/algorithm_data/data", String::as_str);
+            for file in algorithm_data::convert_dir(Path::new(dir)).expect("failed to convert") {
+                println!("wrote {}", file.display());
+            }
+            return;
+        }
+        // checks the data in use (with RUSTIKS_DATA_DIR and RUSTIKS_OVERRIDES), all of it
+        // or the files named
+        Some("verify") => {
+            let report = algorithm_data::DataSet::ALL.iter()
+                .filter(|set| args.len() == 1 || args[1..].iter().any(|a| a == set.name()))
+                .fold(algorithm_data::Report::default(), |report, set| report.merge(algorithm_data::verify(*set)));
+            println!("{}", report);
+            if !report.broken().is_empty() { exit(1) }
+            return;
+        }
+        // searches for the algorithms of a last layer file (with moves like "RUF" and a
+        // maximum length), writes the shortest of each case to the directory of --dir
+        Some("generate") => {
+            let usage = "usage: generate oll|pll|coll|zbll|wv|vls [moves] [max length] [--dir dir]";
+            let set = algorithm_data::DataSet::ALL.iter()
+                .find(|set| args.get(1) == Some(&set.name().to_string()) && set.step().is_some())
+                .unwrap_or_else(|| {
+                    eprintln!("{}", usage);
+                    exit(1)
+                });
+            let (args, dir) = match args.iter().position(|a| a == "--dir") {
+                Some(i) if i + 2 == args.len() => (&args[2..i], args.get(i + 1)),
+                Some(_) => {
+                    eprintln!("{}", usage);
+                    exit(1)
+                }
+                None => (&args[2..], None),
+            };
+            let generated = generator(set.step().unwrap(), args).generate();
+            println!("{}", generated);
+            if let Some(dir) = dir {
+                let file = Path::new(dir).join(set.file_name());
+                std::fs::write(&file, generated.to_json()).expect("failed to write");
+                println!("wrote {}", file.display());
+            }
+            return;
+        }
+        // the sheet of every case of a last layer file (or of all of them), written to a
+        // directory as svg if one is given, printed otherwise, with the overrides in use
+        Some("sheets") => {
+            let name = args.get(1).map(String::as_str);
+            let sets = algorithm_data::DataSet::ALL.iter()
+                .filter(|set| set.step().is_some() && (name == Some("all") || name == Some(set.name())))
+                .collect::<Vec<_>>();
+            if sets.is_empty() {
+                eprintln!("usage: sheets oll|pll|coll|zbll|wv|vls|all [dir]");
+                exit(1);
+            }
+            for set in sets {
+                let step = set.step().unwrap();
+                match args.get(2) {
+                    Some(dir) => {
+                        let file = Path::new(dir).join(format!("{}-sheet.svg", set.name()));
+                        std::fs::write(&file, cube::top_view::sheet_svg(step, &cube::svg::SvgOptions::default()))
+                            .expect("failed to write");
+                        println!("wrote {}", file.display());
+                    }
+                    None => println!("{}", cube::top_view::sheet_unicode(step, &cube::color::ColorScheme::default())),
+                }
+            }
+            return;
+        }
+        // compares the two looks of oll.json and pll.json to one look over every state
+        // of the last layer, with the shortest algorithm generate would find (which
+        // isn't always the optimal one)
+        Some("coverage") => {
+            let generator = generator(cube::last_layer::Step::Zbll, &args[1..]);
+            println!("{}", algorithm_data::coverage(&generator));
+            return;
+        }
+        _ => (),
+    }
+
     cross_data();
     f2l_data();
     oll_data();
@@ -62,4 +152,27 @@ fn main() {
 //    println!("{:?}", cube.colored());
 //    cube.transpose(cpos!(1,3), cpos!(2,3));
 //    println!("{:?}", cube.colored());
-}
\ No newline at end of file
+}
+
+// a generator with the moves (like "RUF") and the maximum length of the arguments
+fn generator(step: cube::last_layer::Step, args: &[String]) -> algorithm_data::Generator {
+    let mut generator = algorithm_data::Generator::new(step);
+    if let Some(moves) = args.first() {
+        let moves = moves.chars()
+            .map(cube::algorithm::MoveType::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1)
+            });
+        generator = generator.with_moves(&moves);
+    }
+    if let Some(max_length) = args.get(1) {
+        let max_length = max_length.parse().unwrap_or_else(|_| {
+            eprintln!("invalid max length '{}'", max_length);
+            exit(1)
+        });
+        generator = generator.with_max_length(max_length);
+    }
+    generator
+}
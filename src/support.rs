@@ -75,6 +75,18 @@ impl<C: PartialEq, R> Tern<C, R> {
             }
         }
     }
+
+    // every result, the one of End last
+    pub fn results(&self) -> Vec<&R> {
+        match self {
+            Tern::End(r) => vec![r],
+            Tern::Con(_, r, b) => {
+                let mut results = vec![r];
+                results.extend(b.results());
+                results
+            }
+        }
+    }
 }
 
 #[allow(dead_code)]
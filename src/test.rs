@@ -1,8 +1,35 @@
-#[allow(unused_imports)] use crate::cube::piece::position::CubePosition;
+#[allow(unused_imports)] use crate::cube::piece::position::{CubePosition, EdgePosition};
 #[allow(unused_imports)] use crate::cube::piece::face::Face;
 #[allow(unused_imports)] use crate::cube::{Edge, Corner};
 #[allow(unused_imports)] use crate::cube::transpose::{Transpose, Transposed};
-#[allow(unused_imports)] use crate::cube::algorithm::Algorithm;
+#[allow(unused_imports)] use crate::cube::algorithm::{Algorithm, Move, MoveType, Metric};
+#[allow(unused_imports)] use crate::cube::scramble::Scrambler;
+#[allow(unused_imports)] use crate::cube::Cube;
+#[allow(unused_imports)] use crate::cube::coordinate::*;
+#[allow(unused_imports)] use itertools::Itertools;
+#[allow(unused_imports)] use crate::cube::packed::PackedCube;
+#[allow(unused_imports)] use std::collections::HashSet;
+#[allow(unused_imports)] use crate::cube::symmetry::Symmetry;
+#[allow(unused_imports)] use crate::algorithm_data::{cross_data, f2l_data, oll_data, pll_data, CEPosition, DataSet};
+#[allow(unused_imports)] use crate::algorithm_data::{load_tern, load_last_layer, write_tern, write_last_layer, convert};
+#[allow(unused_imports)] use crate::algorithm_data::{load_overrides, set_overrides};
+#[allow(unused_imports)] use crate::algorithm_data::{cases, last_layer_problem, Generator};
+#[allow(unused_imports)] use crate::algorithm_data::{coll_data, zbll_data};
+#[allow(unused_imports)] use crate::algorithm_data::{coverage, Coverage};
+#[allow(unused_imports)] use crate::cube::solving::{Stages, SolveError};
+#[allow(unused_imports)] use crate::cube::f2l::FRONTS;
+#[allow(unused_imports)] use crate::algorithm_data::{verify_all, verify_cross, verify_f2l, verify_last_layer, Problem};
+#[allow(unused_imports)] use crate::cube::f2l::SlotStatus;
+#[allow(unused_imports)] use crate::cube::mask::Mask;
+#[allow(unused_imports)] use crate::cube::masked::MaskedCube;
+#[allow(unused_imports)] use crate::cube::nxn::{NxnCube, notation::LayerAlgorithm};
+#[allow(unused_imports)] use crate::cube::pocket::{PocketCube, PocketScrambler, POCKET_STATES};
+#[allow(unused_imports)] use crate::cube::svg::SvgOptions;
+#[allow(unused_imports)] use crate::cube::last_layer::{Step, auf};
+#[allow(unused_imports)] use crate::cube::top_view::{sheet_svg, sheet_unicode};
+#[allow(unused_imports)] use crate::cube::color::{ColorScheme, StickerColor};
+#[allow(unused_imports)] use crate::cube::net::{NetMode, NetOptions};
+#[allow(unused_imports)] use crate::support::Tern;
 
 // apply a testing macro with 2 arguments (e.g. assert_eq) to many pairs of inputs
 macro_rules! apply_ab_tests {
@@ -561,4 +588,1022 @@ fn test_algorithm_simplified() {
         ("D2 B2 B2 D L' D'",                        "D' L' D'");
         ("R L2 U' D F2 B R R2 R B' F2 D' U L L R'", "");
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_scrambler() {
+    let scrambles = Scrambler::new(25, 42).take(100).collect::<Vec<_>>();
+
+    // same seed, same scrambles
+    assert_eq!(scrambles, Scrambler::new(25, 42).take(100).collect::<Vec<_>>());
+
+    for scramble in &scrambles {
+        assert_eq!(scramble.len(), 25);
+        let moves = scramble.into_iter().collect::<Vec<_>>();
+        for w in moves.windows(2) {
+            assert_ne!(w[0].move_type(), w[1].move_type(), "{:?}", scramble);
+        }
+        for w in moves.windows(3) {
+            let axes = (w[0].base_move(), w[1].base_move(), w[2].base_move());
+            assert!(!(axes.0 == axes.1 && axes.1 == axes.2), "{:?}", scramble);
+        }
+    }
+
+    // 2-gen
+    for scramble in Scrambler::with_moves(&[MoveType::R, MoveType::U], 15, 7).take(20) {
+        assert_eq!(scramble.len(), 15);
+        assert!(scramble.into_iter().all(|m|
+            m.move_type() == MoveType::R || m.move_type() == MoveType::U
+        ));
+    }
+}
+
+#[test]
+fn test_coordinates() {
+    let solved = Cube::solved();
+    assert_eq!(solved.corner_orientation(), 0);
+    assert_eq!(solved.edge_orientation(), 0);
+    assert_eq!(solved.corner_permutation(), 0);
+    assert_eq!(solved.edge_permutation(), 0);
+    assert_eq!(solved.ud_slice(), UD_SLICES - 1);
+
+    // inverse constructors
+    for c in 0..CORNER_ORIENTATIONS {
+        assert_eq!(Cube::from_corner_orientation(c).corner_orientation(), c);
+    }
+    for c in 0..EDGE_ORIENTATIONS {
+        assert_eq!(Cube::from_edge_orientation(c).edge_orientation(), c);
+    }
+    for c in 0..CORNER_PERMUTATIONS {
+        assert_eq!(Cube::from_corner_permutation(c).corner_permutation(), c);
+    }
+    for c in (0..EDGE_PERMUTATIONS).step_by(99_991) {
+        assert_eq!(Cube::from_edge_permutation(c).edge_permutation(), c);
+    }
+    for c in 0..UD_SLICES {
+        assert_eq!(Cube::from_ud_slice(c).ud_slice(), c);
+    }
+
+    // <U, D, R2, L2, F2, B2> keeps the slice edges in the slice
+    let mut cube = Cube::solved();
+    cube.apply(&Algorithm::from("U D2 R2 F2 L2 B2 U'"));
+    assert_eq!(cube.ud_slice(), UD_SLICES - 1);
+
+    // R and L twist corners but don't flip edges
+    let mut cube = Cube::solved();
+    cube.apply(&Algorithm::from("U D2 R L'"));
+    assert_eq!(cube.edge_orientation(), 0);
+    assert_ne!(cube.corner_orientation(), 0);
+
+    let mut cube = Cube::solved();
+    cube.apply(&Algorithm::from("F"));
+    assert_ne!(cube.edge_orientation(), 0);
+
+    for scramble in Scrambler::new(30, 3).take(20) {
+        let mut cube = Cube::solved();
+        cube.apply(&scramble);
+
+        let co = cube.corner_orientation();
+        assert_eq!(Cube::from_corner_orientation(co).corner_orientation(), co);
+        let ep = cube.edge_permutation();
+        assert_eq!(Cube::from_edge_permutation(ep).edge_permutation(), ep);
+    }
+}
+
+#[test]
+fn test_group_operations() {
+    let algorithms = Scrambler::new(20, 11).take(10).collect::<Vec<_>>();
+
+    for (a, b) in algorithms.iter().tuple_windows() {
+        assert_eq!(
+            Cube::from_algorithm(a) * Cube::from_algorithm(b),
+            Cube::from_algorithm(&(a.clone() + b.clone()))
+        );
+
+        let cube = Cube::from_algorithm(a);
+        assert_eq!(cube.inverse(), Cube::from_algorithm(&a.reversed()));
+        assert!((cube * cube.inverse()).is_solved());
+        assert!((cube.inverse() * cube).is_solved());
+    }
+
+    // associativity and identity
+    let [a, b, c] = [0, 1, 2].map(|i| Cube::from_algorithm(&algorithms[i]));
+    assert_eq!((a * b) * c, a * (b * c));
+    assert_eq!(a * Cube::solved(), a);
+    assert_eq!(Cube::solved() * a, a);
+
+    // conjugation: (R U R') = R * U * R^-1
+    let [r, u] = ["R", "U"].map(|s| Cube::from_algorithm(&Algorithm::from(s)));
+    assert_eq!(r * u * r.inverse(), Cube::from_algorithm(&Algorithm::from("R U R'")));
+}
+
+#[test]
+fn test_move_application() {
+    // orders of some well known algorithms
+    macro_rules! assert_order {
+        ($alg:expr, $order:expr) => {{
+            let algorithm = Algorithm::from($alg);
+            let mut cube = Cube::solved();
+            for i in 1..=$order {
+                cube.apply(&algorithm);
+                assert_eq!(cube.is_solved(), i == $order, "{} after {} times", $alg, i);
+            }
+        }}
+    }
+
+    apply_ab_tests! {
+        assert_order;
+        ("R", 4);
+        ("R2", 2);
+        ("R U R' U'", 6);
+        ("R U", 105);
+        ("R U' R U R U R U' R' U' R2", 3);
+        ("R U R' U' R' F R2 U' R' U' R U R' F'", 2);
+        ("R U2 D' B D'", 1260);
+    }
+
+    // pieces are reported where they are
+    let cube = Cube::from_algorithm(&Algorithm::from("R U F' L2 D B'"));
+    for edge in cube.iter_edges() {
+        assert_eq!(cube.edge_at(edge.pos), edge);
+    }
+    for corner in cube.iter_corners() {
+        assert_eq!(cube.corner_at(corner.pos), corner);
+    }
+
+    // a corner changed in place is put back
+    let mut twisted = Cube::solved();
+    {
+        let mut corner = twisted.corner_at_mut(pos!(0, 1, 2));
+        let p = corner.pos;
+        corner.pos = pos!(p.1, p.2, p.0);
+    }
+    let mut rotated = Cube::solved();
+    rotated.rotate_corner_clockwise_illegal(pos!(0, 1, 2), 2);
+    assert_eq!(twisted, rotated);
+}
+
+// cargo test --release bench_apply_move -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_apply_move() {
+    use std::time::Instant;
+
+    let scramble = Scrambler::new(1000, 0).next().unwrap();
+    let iterations = 10_000;
+
+    let mut cube = Cube::solved();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        cube.apply(&scramble);
+    }
+    let elapsed = start.elapsed();
+
+    let moves = (scramble.len() * iterations) as f64;
+    println!("{:.1} million moves per second", moves / elapsed.as_secs_f64() / 1e6);
+    println!("(solved: {})", cube.is_solved());
+}
+
+#[test]
+fn test_packed_cube() {
+    let cubes = Scrambler::new(25, 5)
+        .take(50)
+        .map(|a| Cube::from_algorithm(&a))
+        .collect::<Vec<_>>();
+
+    for (a, b) in cubes.iter().tuple_windows() {
+        assert_eq!(Cube::from(a.packed()), *a);
+        assert_eq!(a.packed().cmp(&b.packed()), a.cmp(b));
+
+        let json = serde_json::to_string(&a.packed()).unwrap();
+        assert_eq!(json.len(), 34);
+        assert_eq!(serde_json::from_str::<PackedCube>(&json).unwrap(), a.packed());
+    }
+
+    assert_eq!(
+        serde_json::to_string(&Cube::solved().packed()).unwrap(),
+        "\"000000053977000000123456789ab000\""
+    );
+    assert!(serde_json::from_str::<PackedCube>("\"000000053977000000123456789aa000\"").is_err());
+
+    // number of states at distance <= 3 from solved
+    let moves = MoveType::ALL
+        .iter()
+        .flat_map(|t| (1..=3).map(move |n| Cube::from_algorithm(&vec![Move::new(*t, n)].into_iter().collect())))
+        .collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+    seen.insert(Cube::solved().packed());
+    let mut frontier = vec![Cube::solved()];
+    for _ in 0..3 {
+        frontier = frontier
+            .iter()
+            .flat_map(|c| moves.iter().map(move |m| *c * *m))
+            .filter(|c| seen.insert(c.packed()))
+            .collect();
+    }
+    assert_eq!(seen.len(), 1 + 18 + 243 + 3240);
+}
+
+#[test]
+fn test_symmetries() {
+    let symmetries = Symmetry::all();
+    assert_eq!(symmetries.len(), 48);
+    assert_eq!(symmetries.iter().unique_by(|s| format!("{:?}", s)).count(), 48);
+    assert_eq!(symmetries.iter().filter(|s| s.is_reflection()).count(), 24);
+    assert_eq!(symmetries[0], Symmetry::identity());
+
+    // rotations agree with Transpose
+    let (from, to) = (cpos!(1, 3), cpos!(2, 0));
+    let rotation = Symmetry::rotation(from, to);
+    assert!(!rotation.is_reflection());
+
+    let algorithm = Algorithm::from("R U2 F' L D B2 R'");
+    let cube = Cube::from_algorithm(&algorithm);
+    assert_eq!(cube.symmetric(&rotation), cube.transposed(from, to));
+
+    for s in symmetries {
+        // symmetric algorithms produce symmetric states
+        assert_eq!(
+            Cube::from_algorithm(&algorithm.symmetric(s)),
+            cube.symmetric(s)
+        );
+
+        assert_eq!(cube.symmetric(s).symmetric(&s.inverse()), cube);
+        for t in symmetries.iter().step_by(5) {
+            assert_eq!(cube.symmetric(s).symmetric(t), cube.symmetric(&(*s * *t)));
+        }
+
+        // all symmetric states have the same canonical state
+        let (canonical, used) = cube.symmetry_canonical();
+        assert_eq!(cube.symmetric(&used), canonical);
+        assert_eq!(cube.symmetric(s).symmetry_canonical().0, canonical);
+    }
+
+    // a superflip is symmetric in every way
+    let superflip = Cube::from_algorithm(&Algorithm::from(
+        "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+    ));
+    assert!(symmetries.iter().all(|s| superflip.symmetric(s) == superflip));
+}
+
+#[test]
+fn test_last_layer_cases() {
+    // the last layer is face 3, the algorithms are written for the top
+    let frame = Symmetry::rotation_to(Face::new(3), Face::new(5));
+    let d = |times: usize| (0..times).fold(Cube::solved(), |c, _| c * Cube::from_algorithm(&Algorithm::from("D")));
+    let case_of = |alg: &Algorithm| Cube::from_algorithm(&alg.reversed().symmetric(&frame));
+
+    for (i, (key, alg)) in oll_data().iter().enumerate() {
+        let mut cube = d(i / 4 % 4) * case_of(alg) * d(i % 4);
+        let case = cube.oll_case().expect("unrecognized oll case");
+        assert_eq!(&case.key, key);
+        cube.apply(&case.algorithm);
+        assert!(cube.pll_case().is_some(), "{} not oriented", case.name);
+    }
+
+    for (i, (key, alg)) in pll_data().iter().enumerate() {
+        let mut cube = d(i / 4 % 4) * case_of(alg) * d(i % 4);
+        let case = cube.pll_case().expect("unrecognized pll case");
+        assert_eq!(&case.key, key);
+        cube.apply(&case.algorithm);
+        assert!(cube.is_solved(), "{} not solved", case.name);
+    }
+
+    let sune = case_of(&Algorithm::from("R U R' U R U2 R'"));
+    assert_eq!(sune.oll_case().unwrap().name, "OLL 27");
+    assert!(sune.pll_case().is_none());
+    let t_perm = case_of(&Algorithm::from("R U R' U' R' F R2 U' R' U' R U R' F'")) * d(1);
+    assert_eq!(t_perm.pll_case().unwrap().name, "T-perm");
+    assert_eq!(Cube::solved().pll_case().unwrap().name, "PLL skip");
+    assert!(Cube::from_algorithm(&Algorithm::from("R")).oll_case().is_none());
+}
+
+#[test]
+fn test_f2l_slots() {
+    assert!(Cube::solved().f2l_slots().iter().all(|s| s.is_solved()));
+
+    // the last layer doesn't matter
+    let oll = Cube::from_algorithm(&Algorithm::from("D R D R' D R D2 R'"));
+    assert!(oll.f2l_slots().iter().all(|s| s.is_solved()));
+
+    let frame = Symmetry::rotation_to(Face::new(3), Face::new(5));
+    for (key, tern) in f2l_data().iter().take(20) {
+        let alg = match tern { Tern::End(alg) => alg, Tern::Con(_, alg, _) => alg };
+        let case = Cube::from_algorithm(&alg.symmetric(&frame).reversed());
+        assert_eq!(case.f2l_slot(Face::new(1)).status, SlotStatus::Unsolved(*key), "{:?}", alg);
+
+        // the same case in every slot
+        for &front in &[1, 2, 4, 5] {
+            let cube = case.transposed(cpos!(1, 3), cpos!(front, 3));
+            let slot = cube.f2l_slots().into_iter().find(|s| s.front == Face::new(front)).unwrap();
+            assert_eq!(slot.status, SlotStatus::Unsolved(*key));
+            assert!(slot.corner.0 == Face::new(0) && slot.edge.sorted() == slot.edge);
+        }
+    }
+
+    let slots = Cube::from_algorithm(&Algorithm::from("R")).f2l_slots();
+    let unsolved = slots.iter().filter(|s| !s.is_solved()).map(|s| s.edge).collect::<Vec<_>>();
+    assert_eq!(unsolved, vec![pos!(1, 2), pos!(2, 4)]);
+}
+
+#[test]
+fn test_masks() {
+    let solved = Cube::solved();
+    assert!(solved.matches_mask(&Mask::solved()));
+    assert!((0..6).all(|f| solved.is_oll_solved(Face::new(f))));
+
+    // the last layer of a cross on face 0 is face 3
+    let sune = Cube::from_algorithm(&Algorithm::from("D R D R' D R D2 R'"));
+    assert!(sune.is_f2l_solved(Face::new(0)) && !sune.is_oll_solved(Face::new(0)));
+    assert!(!sune.is_cross_solved(Face::new(3)));
+    let t_perm = Cube::from_algorithm(&Algorithm::from("R U R' U' R' F R2 U' R' U' R U R' F'"));
+    assert!(t_perm.is_oll_solved(Face::new(3)) && !t_perm.is_oll_solved(Face::new(0)));
+
+    // an r move only turns the last layer of a cross on face 5
+    let cube = Cube::from_algorithm(&Algorithm::from("R"));
+    assert!(cube.is_oll_solved(Face::new(5)) && !cube.matches_mask(&Mask::solved()));
+    assert_eq!((0..6).filter(|f| cube.is_cross_solved(Face::new(*f))).count(), 1);
+
+    // a turned face keeps its pieces but not its cross
+    let cube = Cube::from_algorithm(&Algorithm::from("L"));
+    assert!(cube.is_cross_solved(Face::new(2)) && !cube.is_cross_solved(Face::new(5)));
+    assert!(cube.matches_mask(&Mask { edges: vec![edge!(1, 2)], corners: vec![] }));
+}
+
+#[test]
+fn test_masked_cube() {
+    // only the last layer edges (cross on face 0)
+    let edges = Mask { edges: Face::new(3).adjacent_edges().to_vec(), corners: vec![] };
+    let sune = Algorithm::from("D R D R' D R D2 R'");
+    let mut masked = MaskedCube::new(Cube::solved(), &edges);
+
+    // corners are wildcards, an a-perm only moves corners
+    let a_perm = Algorithm::from("R' F R' B2 R F' R' B2 R2");
+    assert!(masked.matches(&Cube::from_algorithm(&a_perm)));
+    assert!(!masked.matches(&Cube::from_algorithm(&sune)));
+
+    masked.apply(&sune);
+    let other = MaskedCube::new(Cube::from_algorithm(&(sune.clone() + a_perm)), &edges);
+    assert!(masked == other);
+    assert_eq!(vec![masked, other].into_iter().collect::<HashSet<_>>().len(), 1);
+    assert!(masked != MaskedCube::new(Cube::from_algorithm(&sune), &Mask::solved()));
+
+    // 4 edges and the 6 centers are shown
+    let shown = format!("{:?}", masked).chars().filter(|c| c.is_numeric()).count();
+    assert_eq!(shown, 4 * 2 + 6);
+    assert_eq!(format!("{}", masked).matches('.').count(), 54 - shown);
+}
+
+#[test]
+fn test_nxn_cube() {
+    // a 3x3 NxnCube looks the same as a Cube
+    let scramble = Algorithm::from("R U2 F' L D B2 R' U F2 D' L2 B");
+    let mut cube = NxnCube::solved(3);
+    cube.apply(&LayerAlgorithm::from(&scramble));
+    assert_eq!(format!("{:?}", cube), format!("{:?}", Cube::from_algorithm(&scramble)));
+
+    let after = |size: usize, alg: &str| {
+        let mut cube = NxnCube::solved(size);
+        cube.apply(&LayerAlgorithm::from(alg));
+        cube
+    };
+
+    // wide moves and rotations
+    assert_eq!(after(4, "Rw"), after(4, "R 2R"));
+    assert_eq!(after(4, "r"), after(4, "Rw"));
+    assert_eq!(after(5, "3Rw'"), after(5, "R' 2R' 3R'"));
+    assert_eq!(after(4, "x"), after(4, "4Rw"));
+    assert_eq!(after(4, "x"), after(4, "R 2R 3R L'"));
+    assert!(after(4, "x y2 z'").is_solved() && after(4, "x y2 z'") != NxnCube::solved(4));
+    assert!(!after(5, "3Rw U2 3Rw'").is_solved());
+
+    let alg = LayerAlgorithm::from("3Rw 2U' F2 r Lw' x");
+    assert_eq!(format!("{:?}", alg), "3Rw 2U' F2 Rw Lw' x");
+    assert_eq!(after(4, &format!("{:?} {:?}", alg, alg.reversed())), NxnCube::solved(4));
+    assert!(std::panic::catch_unwind(|| LayerAlgorithm::from("R2'x")).is_err());
+    assert!(std::panic::catch_unwind(|| LayerAlgorithm::from("Rw2'")).is_ok());
+
+    // pieces
+    let cube = after(4, "Rw");
+    assert_eq!(cube.corner(pos!(0, 1, 2)), pos!(1, 3, 2));
+    assert_eq!(cube.wings(pos!(0, 2)), vec![pos!(1, 2), pos!(1, 2)]);
+    assert_eq!(cube.centers(Face::new(0)), vec![vec![Face::new(0), Face::new(1)]; 2]);
+    assert_eq!(after(2, "R U").wings(pos!(0, 1)), vec![]);
+    assert_eq!(format!("{}", after(5, "")).lines().next().unwrap(), "           W W W W W");
+}
+
+#[test]
+fn test_pocket_cube() {
+    assert!(PocketCube::solved().is_solved());
+
+    // d, b and l turns are u, f and r turns of a rotated puzzle
+    let cube = PocketCube::from_algorithm(&Algorithm::from("D"));
+    assert_eq!(cube.distance(Metric::Htm), 1);
+    assert!(PocketCube::from_algorithm(&Algorithm::from("R L'")).is_solved());
+    assert_eq!(PocketCube::from(&Cube::from_algorithm(&Algorithm::from("R U2 R'"))).distance(Metric::Qtm), 4);
+
+    let mut scrambler = PocketScrambler::new(3);
+    for scramble in scrambler.by_ref().take(20) {
+        let mut cube = PocketCube::from_algorithm(&scramble);
+        assert_eq!(cube.distance(Metric::Htm) as usize, scramble.len());
+        assert!(scramble.len() <= 11, "god's number in htm is 11");
+
+        let qtm = cube.solution(Metric::Qtm);
+        assert_eq!(qtm.length(Metric::Qtm), cube.distance(Metric::Qtm) as usize);
+        assert!(qtm.length(Metric::Qtm) <= 14, "god's number in qtm is 14");
+        cube.apply(&qtm);
+        assert!(cube.is_solved());
+    }
+
+    let corners = PocketCube::solved().iter_corners().collect::<Vec<_>>();
+    assert_eq!(PocketCube::from_corners(corners.clone()).unwrap().distance(Metric::Htm), 0);
+    let scrambled = PocketCube::from_algorithm(&Algorithm::from("R U F'"));
+    assert_eq!(PocketCube::from_corners(scrambled.iter_corners()), Some(scrambled));
+    // a corner twisted in place isn't a legal state (the sum of twists isn't 0)
+    let mut twisted = Cube::solved();
+    twisted.rotate_corner_clockwise_illegal(pos!(0, 1, 2), 1);
+    assert_eq!(PocketCube::from_corners(twisted.iter_corners()), None);
+    // neither is a corner in two places
+    let mut doubled = corners.clone();
+    doubled[1] = Corner::new(corners[0].id, corners[1].pos);
+    assert_eq!(PocketCube::from_corners(doubled), None);
+    assert_eq!(PocketCube::from_corners(vec![corners[0], corners[0]]), None);
+    assert_eq!(POCKET_STATES, 5040 * 729);
+}
+
+#[test]
+fn test_supercube() {
+    let after = |alg: &str| {
+        let mut cube = Cube::solved().with_centers().unwrap();
+        cube.apply(&Algorithm::from(alg));
+        cube
+    };
+
+    // the pieces come back but the center of face 0 is upside down
+    let half_turn = "R L U2 R' L' U R L U2 R' L' U";
+    assert!(Cube::from_algorithm(&Algorithm::from(half_turn)).is_solved());
+    assert!(!after(half_turn).is_solved());
+    assert_eq!(after(half_turn).center_orientation(Face::new(0)), Some(2));
+    assert!(after("U U U U").is_solved() && after("R U R' U'").center_orientation(Face::new(0)) == Some(0));
+    assert_eq!(Cube::solved().center_orientation(Face::new(0)), None);
+
+    // centers follow symmetries and products
+    let alg = Algorithm::from("R U2 F' L D B");
+    for s in Symmetry::all() {
+        assert_eq!(after(&format!("{:?}", alg)).symmetric(s), after(&format!("{:?}", alg.symmetric(s))));
+    }
+    let cube = after(&format!("{:?}", alg));
+    assert!((cube * cube.inverse()).is_solved() && cube * cube.inverse() != Cube::solved());
+    assert_eq!(Cube::solved() * cube, cube);
+
+    for scramble in Scrambler::new(25, 38).take(10) {
+        let cube = Cube::from_algorithm(&scramble);
+        let mut solved = cube;
+        solved.apply(&cube.solution().unwrap());
+        assert!(solved.is_solved(), "{:?} not solved", scramble);
+
+        let supercube = after(&format!("{:?}", scramble));
+        let mut solved = supercube;
+        solved.apply(&supercube.solution().unwrap());
+        assert!(solved.is_solved() && solved.is_supercube(), "{:?} centers not solved", scramble);
+    }
+
+    // a corner twisted in place is no case of the last layer
+    let mut twisted = Cube::solved();
+    twisted.rotate_corner_clockwise_illegal(pos!(3, 1, 2), 1);
+    assert!(matches!(twisted.solution(), Err(SolveError::Unrecognized(Step::Oll, _))));
+    // supercubes aren't packed, they differ from the cube without centers
+    let supercube = Cube::solved().with_centers().unwrap();
+    assert!(supercube != Cube::solved() && std::panic::catch_unwind(|| supercube.packed()).is_err());
+
+    // solved centers only go with evenly permuted pieces
+    let u = Cube::from_algorithm(&Algorithm::from("U"));
+    assert!(u.with_centers().is_none() && (u * u).with_centers().is_some());
+    for scramble in Scrambler::new(25, 39).take(20) {
+        if let Some(supercube) = Cube::from_algorithm(&scramble).with_centers() {
+            let mut solved = supercube;
+            solved.apply(&supercube.solution().unwrap());
+            assert!(solved.is_solved(), "{:?} centers not solved", scramble);
+        }
+    }
+}
+
+// the color override of colored is global and the tests run in parallel, so the tests
+// which turn colors on take turns
+static COLORS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[allow(dead_code)]
+fn with_colors<T>(f: impl FnOnce() -> T) -> T {
+    let _turn = COLORS.lock().unwrap_or_else(|e| e.into_inner());
+    colored::control::set_override(true);
+    let result = f();
+    colored::control::unset_override();
+    result
+}
+
+// the faces of the fills of an svg, as digits
+#[allow(dead_code)]
+fn svg_faces(svg: &str, scheme: &ColorScheme) -> String {
+    svg.split("fill=\"")
+        .skip(1)
+        .map(|s| scheme.faces.iter().position(|c| c.hex() == s[..7]).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_svg() {
+    let svg = Cube::solved().to_svg();
+    assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("<rect ").count(), 54);
+    assert_eq!(svg.matches(r##"fill="#ffffff""##).count(), 9);
+
+    let options = SvgOptions { sticker_size: 10, ..SvgOptions::default() };
+    let svg = Cube::from_algorithm(&Algorithm::from("R")).to_svg_with(&options);
+    assert!(svg.contains(r#"width="130" height="98""#));
+
+    // the stickers are in the same order as in the text net
+    let fills = svg_faces(&svg, &options.scheme);
+    let text = format!("{:?}", Cube::from_algorithm(&Algorithm::from("R")));
+    let digits = |s: &str| s.chars().filter(|c| c.is_numeric()).collect::<String>();
+    let by_face = |s: &str| {
+        let lines = s.lines().filter(|l| !l.trim().is_empty()).collect::<Vec<_>>();
+        let band = (3..6).map(|i| lines[i].split("  ").collect::<Vec<_>>()).collect::<Vec<_>>();
+        let mut faces = vec![digits(&lines[..3].join(""))];
+        faces.extend((0..4).map(|f| digits(&band.iter().map(|l| l[f]).join(""))));
+        faces.push(digits(&lines[6..].join("")));
+        faces.join("")
+    };
+    assert_eq!(fills, by_face(&text));
+}
+
+#[test]
+fn test_top_view() {
+    // the oriented corner of a sune is in front on the left, the others show the last
+    // layer's color on the front, right and back
+    let sune = Cube::from_last_layer_algorithm(&Algorithm::from("R U R' U R U2 R'"));
+    assert_eq!(sune.top_view(Step::Oll).to_unicode(&ColorScheme::western()), [
+        "  🟨⬛⬛  ",
+        "⬛⬛🟨⬛🟨",
+        "⬛🟨🟨🟨⬛",
+        "⬛🟨🟨⬛⬛",
+        "  ⬛⬛🟨  ",
+    ].join("\n"));
+    assert!(Cube::solved().top_view(Step::Pll).cycles().is_empty());
+
+    // the last layer is turned so that as few pieces as possible move
+    let t_perm = Algorithm::from("R U R' U' R' F R2 U' R' U' R U R' F'");
+    let cycles = Cube::from_last_layer_algorithm(&t_perm).top_view(Step::Pll).cycles();
+    assert_eq!(cycles, vec!["UBR → UFR → UBR", "UL → UR → UL"]);
+    let ua_perm = Cube::from_last_layer_algorithm(&Algorithm::from("R U' R U R U R U' R' U' R2"));
+    for turns in 0..4 {
+        let turned = Cube::from_algorithm(&Algorithm::from("D ".repeat(turns))) * ua_perm;
+        assert_eq!(turned.top_view(Step::Pll).cycles(), vec!["UL → UF → UR → UL"]);
+    }
+
+    let svg = Cube::from_last_layer_algorithm(&t_perm).top_view(Step::Pll).to_svg(&SvgOptions::default());
+    assert_eq!((svg.matches("<rect ").count(), svg.matches("<line ").count()), (21, 2));
+    assert_eq!(svg.matches("marker-start").count(), 2);
+
+    // every case but the skips
+    assert_eq!(sheet_unicode(Step::Oll, &ColorScheme::default()).matches("OLL ").count(), 57);
+    let sheet = sheet_svg(Step::Pll, &SvgOptions::default());
+    assert_eq!(sheet.matches("-perm</text>").count(), 21);
+    assert!(sheet.find("Aa-perm").unwrap() < sheet.find("Z-perm").unwrap());
+    // as main writes them for every file
+    for step in &[Step::Coll, Step::Wv] {
+        let cases = step.data().values().filter(|alg| !alg.is_empty()).count();
+        assert_eq!(sheet_svg(*step, &SvgOptions::default()).matches("font-weight=\"bold\"").count(), cases);
+    }
+}
+
+#[test]
+fn test_isometric() {
+    let options = SvgOptions { sticker_size: 10, ..SvgOptions::default() };
+    let fills = |cube: &Cube, position: CubePosition| {
+        svg_faces(&cube.isometric(position).to_svg(&options), &options.scheme)
+    };
+
+    // up, front and right, row by row
+    assert_eq!(fills(&Cube::solved(), cpos!(1, 3)), "000000000111111111222222222");
+    assert_eq!(fills(&Cube::solved(), cpos!(4, 3)), "000000000444444444555555555");
+    assert_eq!(fills(&Cube::solved(), cpos!(3, 1)), "444444444333333333555555555");
+
+    // r brings the right column of the front up
+    let cube = Cube::from_algorithm(&Algorithm::from("R"));
+    assert_eq!(fills(&cube, cpos!(1, 3)), "001001001113113113222222222");
+
+    // six lines, every sticker is one line high
+    let text = format!("{}", cube.isometric(cpos!(1, 3)));
+    assert_eq!(text.lines().count(), 6);
+    assert!(text.lines().next().unwrap().starts_with("   "));
+}
+
+#[test]
+fn test_color_scheme() {
+    let western = ColorScheme::western();
+    let japanese = ColorScheme::japanese();
+    assert_eq!(format!("{}", Face::new(2)), "B");
+    assert_eq!(western.face_of('B'), Some(Face::new(2)));
+    assert_eq!(japanese.face_of('B'), Some(Face::new(3)));
+    assert_eq!(western.face_of('X'), None);
+    assert_eq!(western.faces.iter().map(|c| c.emoji()).join(""), "⬜🟥🟦🟨🟧🟩");
+    assert_eq!(western.unknown.emoji(), "⬛");
+
+    // the same svg with other fills
+    let cube = Cube::from_algorithm(&Algorithm::from("R U F'"));
+    let options = SvgOptions { scheme: japanese, ..SvgOptions::default() };
+    assert_eq!(svg_faces(&cube.to_svg_with(&options), &japanese), svg_faces(&cube.to_svg(), &western));
+    assert_eq!(cube.to_svg_with(&options).matches(&StickerColor::BLUE.hex()).count(), 9);
+    let letters = format!("{}", cube.net(NetOptions::new(NetMode::Ascii).with_scheme(japanese)));
+    assert_eq!(letters.matches('B').count(), 9);
+
+    // 24-bit colors
+    let truecolor = with_colors(|| western.with_truecolor(true).paint("  ", Some(Face::new(4))));
+    assert_eq!(truecolor, "\x1b[48;2;255;88;0m  \x1b[0m");
+
+    // a scheme turned so that white is in front
+    let turned = Symmetry::rotation_to(Face::new(1), Face::new(3));
+    let mut faces = western.faces;
+    for f in 0..6 {
+        faces[turned.image(Face::new(f)).index()] = western.faces[f as usize];
+    }
+    let front_white = ColorScheme::custom(faces);
+    let recolored = cube.recolored(&western, &front_white);
+    assert_eq!(recolored.cube, cube.symmetric(&turned));
+    assert_eq!(recolored.scheme, front_white);
+    assert_eq!(recolored.cube.recolored(&front_white, &western).cube, cube);
+    assert!(Cube::solved().recolored(&western, &front_white).cube.is_solved());
+
+    // japanese isn't western turned, its colors go on the faces where it has them
+    let recolored = cube.recolored(&western, &japanese.with_truecolor(true));
+    assert_eq!((recolored.cube, recolored.scheme), (cube, japanese.with_truecolor(true)));
+    let options = SvgOptions { scheme: recolored.scheme, ..SvgOptions::default() };
+    let svg = recolored.cube.to_svg_with(&options);
+    assert_ne!(svg, cube.to_svg());
+    assert_eq!(svg_faces(&svg, &recolored.scheme), svg_faces(&cube.to_svg(), &western));
+    assert_eq!(svg.matches(&StickerColor::BLUE.hex()).count(), cube.to_svg().matches(&StickerColor::YELLOW.hex()).count());
+}
+
+#[test]
+fn test_net() {
+    // ascii is Display with the letters of the scheme
+    let cube = Cube::from_algorithm(&Algorithm::from("R U2 F' L D B2"));
+    let ascii = NetOptions::new(NetMode::Ascii);
+    assert_eq!(format!("{}", cube.net(ascii)), format!("{}", cube));
+    let nxn = NxnCube::solved(4);
+    assert_eq!(format!("{}", nxn.colored().with_options(ascii)), format!("{}", nxn));
+    let masked = MaskedCube::new(cube, &Mask::cross());
+    assert_eq!(format!("{}", masked.colored().with_options(ascii)).matches('-').count(), 40);
+
+    let solved = format!("{}", Cube::solved().net(ascii.with_spacing(0, 0)));
+    assert_eq!(solved.lines().collect::<Vec<_>>(), [
+        "   WWW",
+        "   WWW",
+        "   WWW",
+        "GGGRRRBBBOOO",
+        "GGGRRRBBBOOO",
+        "GGGRRRBBBOOO",
+        "   YYY",
+        "   YYY",
+        "   YYY",
+    ]);
+
+    // squares are two columns wide, spaced faces are two columns and one line apart
+    let unicode = format!("{}", Cube::solved().net(NetOptions::new(NetMode::Unicode)));
+    let lines = unicode.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "        ⬜⬜⬜");
+    assert_eq!(lines[3], "");
+    assert_eq!(lines[4], "🟩🟩🟩  🟥🟥🟥  🟦🟦🟦  🟧🟧🟧");
+
+    // the colors of the scheme in ansi, only 6 painted stickers on a line of the band
+    let ansi = with_colors(|| format!("{}", Cube::solved().net(NetOptions::new(NetMode::Ansi).with_spacing(1, 1))));
+    assert_eq!(ansi.lines().nth(4).unwrap().matches("\x1b[").count(), 2 * 12);
+    assert!(!ansi.contains('W'));
+}
+
+#[test]
+fn test_algorithm_data() {
+    // the embedded files parse (unless RUSTIKS_DATA_DIR replaces them), every position
+    // of the piece has an entry and the last layer has the skips
+    assert_eq!(cross_data().len(), 24);
+    assert_eq!(f2l_data().len(), 24 * 16);
+    assert_eq!(oll_data().len(), 57 + 1);
+    assert_eq!(pll_data().len(), 21 + 1);
+    assert!(oll_data().values().any(|alg| alg.is_empty()));
+
+    // loaded once for all threads
+    let addresses = (0..4)
+        .map(|_| std::thread::spawn(|| f2l_data() as *const _ as usize))
+        .map(|thread| thread.join().unwrap())
+        .collect::<HashSet<_>>();
+    assert_eq!(addresses.len(), 1);
+}
+
+#[test]
+fn test_data_format() {
+    // the embedded files are as the converter writes them
+    assert_eq!(write_tern(cross_data()), include_str!("algorithm_data/data/cross.json"));
+    assert_eq!(write_tern(f2l_data()), include_str!("algorithm_data/data/f2l.json"));
+    assert_eq!(write_last_layer(Step::Oll, oll_data()), include_str!("algorithm_data/data/oll.json"));
+    assert_eq!(write_last_layer(Step::Pll, pll_data()), include_str!("algorithm_data/data/pll.json"));
+
+    // the old format
+    let cross = convert(DataSet::Cross, "// format: ee\n01:0204\"L2 U' F2\"\"D\"\n10:\"L' F'\"\n");
+    assert!(cross.contains(r#"{"position":"DL","branches":[{"solved":["DB","DR"],"algorithm":"L2 U' F2"}],"algorithm":"D"}"#));
+    let cross = load_tern::<EdgePosition>("cross.json", &cross).unwrap();
+    assert_eq!(cross[&pos!(0, 1)].eval(&vec![pos!(0, 2), pos!(0, 4)]), &Algorithm::from("L2 U' F2"));
+    assert_eq!(cross[&pos!(0, 1)].eval(&vec![]), &Algorithm::from("D"));
+    let f2l = convert(DataSet::F2l, "01215:02424\"L U2 F2 L' F2\"\"L U\"\n");
+    assert!(f2l.contains(r#"{"position":"DLB LF","branches":[{"solved":["DBR BR"],"#));
+    let pll = convert(DataSet::Pll, "0,1,2,5,4,7,6,3:\"R B' R F2 R' B R F2 R2\"\n");
+    assert!(pll.contains(r#"{"name":"Ab-perm","algorithm":"R B' R F2 R' B R F2 R2"}"#));
+
+    // errors say where they are
+    let error = |text: &str| {
+        let error = load_last_layer(Step::Pll, "pll.json", text).unwrap_err();
+        (error.line, error.message)
+    };
+    let file = |cases: &[&str]| format!("{{\n  \"version\": 1,\n  \"cases\": [\n{}\n  ]\n}}\n", cases.join(",\n"));
+    let t_perm = r#"    {"name":"T-perm","algorithm":"R U R' U' R' F R2 U' R' U' R U R' F'"}"#;
+    assert_eq!(error(&file(&[t_perm, r#"    {"name":"X-perm","algorithm":""}"#])).0, 5);
+    assert!(error(&file(&[t_perm, r#"    {"name":"X-perm","algorithm":""}"#])).1.contains("unknown case \"X-perm\""));
+    assert_eq!(error(&file(&[t_perm, t_perm])), (5, "duplicate case".to_string()));
+    assert_eq!(error(&file(&[r#"{"name":"T-perm","algorithm":"R U R' X"}"#])), (4, "invalid move type 'X'".to_string()));
+    assert_eq!(error(&file(&[t_perm, r#"{"name":"T-perm"}"#])).1, "missing field `algorithm`");
+    assert_eq!(error(&file(&[t_perm]).replace("1", "2")).1, "unsupported version 2, expected 1");
+    let error = load_tern::<CEPosition>("f2l.json", &file(&[r#"    {"position":"UFD FR","algorithm":""}"#])).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (4, "invalid position \"UFD FR\""));
+    assert!(error.to_string().starts_with("f2l.json:4:"));
+}
+
+#[test]
+fn test_verify() {
+    // the cases are built from their keys
+    for step in [Step::Oll, Step::Pll, Step::Coll, Step::Zbll, Step::Wv, Step::Vls] {
+        for key in step.keys() {
+            assert_eq!(step.case(&key).last_layer_case(step).unwrap().key, key);
+        }
+    }
+    assert_eq!(Cube::from_oll_key(&Step::Oll.keys()[27]).oll_case().unwrap().name, "OLL 27");
+
+    let report = verify_all();
+    assert_eq!(report.entries, 24 + 384 + 58 + 22 + 43 + 494 + 27 + 216);
+    assert!(report.broken().is_empty(), "{}", report);
+
+    // typos are found
+    let file = |cases: &[&str]| format!("{{\n  \"version\": 1,\n  \"cases\": [\n{}\n  ]\n}}\n", cases.join(",\n"));
+    let cross = load_tern::<EdgePosition>("cross.json", &file(&[
+        r#"{"position":"DL","branches":[{"solved":["DB","DR"],"algorithm":"L2 U' F2"}],"algorithm":"D'"}"#,
+        r#"{"position":"LD","branches":[{"solved":["DR","DB"],"algorithm":"L' F'"}],"algorithm":"L' F'"}"#,
+        r#"{"position":"DR","branches":[{"solved":["DL","DB"],"algorithm":"R2 U F2 U2"}],"algorithm":"D'"}"#,
+    ])).unwrap();
+    let report = verify_cross(&cross);
+    let problem = |case: &str, solved: &[&str]| report.findings.iter()
+        .find(|f| f.case == case && f.solved == solved)
+        .map(|f| f.problem.clone());
+    assert_eq!(problem("DL", &[]), Some(Problem::Broken("it doesn't solve DF".to_string())));
+    assert!(matches!(problem("LD", &["DR", "DB"]), Some(Problem::Unused(_))));
+    assert!(matches!(problem("DR", &["DL"]), Some(Problem::Suboptimal(alg)) if alg.len() == 3));
+    assert_eq!(problem("FD", &[]), Some(Problem::Missing));
+    assert_eq!(report.broken().len(), 2 + 20);
+
+    let f2l = load_tern::<CEPosition>("f2l.json", &file(&[
+        r#"{"position":"UFR FR","branches":[{"solved":["DLB LB"],"algorithm":"R U' R'"}],"algorithm":"F' U F R U2 R'"}"#,
+    ])).unwrap();
+    let report = verify_f2l(&f2l);
+    let finding = report.findings.iter().find(|f| f.case == "UFR FR").unwrap();
+    assert_eq!(finding.solved, vec!["DLB LB"]);
+    assert!(matches!(finding.problem, Problem::Broken(_)), "{}", finding);
+
+    let mut pll = pll_data().clone();
+    pll.insert(Step::Pll.key("T-perm").unwrap(), Algorithm::from("R U R' U' R' F R2 U' R' U' R U R'"));
+    pll.remove(&Step::Pll.key("Z-perm").unwrap());
+    let report = verify_last_layer(Step::Pll, &pll);
+    let broken = report.broken().iter().map(|f| (f.case.as_str(), f.problem.clone())).collect::<Vec<_>>();
+    assert_eq!(broken, vec![
+        ("T-perm", Problem::Broken("it breaks the first two layers".to_string())),
+        ("Z-perm", Problem::Missing),
+    ]);
+}
+
+#[test]
+fn test_overrides() {
+    let file = |oll: &[&str], pll: &[&str]| format!(
+        "{{\n  \"version\": 1,\n  \"oll\": [\n{}\n  ],\n  \"pll\": [\n{}\n  ]\n}}\n",
+        oll.join(",\n"), pll.join(",\n"),
+    );
+    let sune = r#"    {"name":"OLL 27","algorithm":"U L' U2 L U L' U L"}"#;
+    let t_perm = r#"    {"name":"T-perm","algorithm":"F R U' R' U R U R2 F' R U R U' R'"}"#;
+    let ub_perm = r#"    {"key":[6,1,2,3,0,5,4,7],"algorithm":"L' U L' U' L' U' L' U L U L2"}"#;
+    let overrides = load_overrides("overrides.json", &file(&[sune], &[t_perm, ub_perm])).unwrap();
+    assert_eq!(overrides.oll.len(), 1);
+    assert_eq!(overrides.pll[&Step::Pll.key("Ub-perm").unwrap()], Algorithm::from("L' U L' U' L' U' L' U L U L2"));
+
+    // they replace the entries of the data, which stays correct
+    let oll = overrides.apply(Step::Oll, oll_data().clone());
+    assert_eq!(oll.len(), oll_data().len());
+    assert_eq!(oll[&Step::Oll.key("OLL 27").unwrap()], Algorithm::from("U L' U2 L U L' U L"));
+    assert!(verify_last_layer(Step::Oll, &oll).broken().is_empty());
+    assert!(verify_last_layer(Step::Pll, &overrides.apply(Step::Pll, pll_data().clone())).broken().is_empty());
+
+    // the others are rejected where they are
+    let error = |oll: &[&str], pll: &[&str]| {
+        let error = load_overrides("overrides.json", &file(oll, pll)).unwrap_err();
+        (error.line, error.message)
+    };
+    let (line, message) = error(&[], &[t_perm, r#"    {"name":"T-perm","algorithm":"R U R' U' R' F R2 U' R' U' R U R'"}"#]);
+    assert_eq!(line, 8);
+    assert_eq!(message, "\"R U R' U' R' F R2 U' R' U' R U R'\" doesn't solve T-perm, it breaks the first two layers");
+    assert_eq!(error(&[r#"    {"name":"OLL 27","algorithm":"R U2 R' U' R U' R'"}"#], &[]).1,
+        "\"R U2 R' U' R U' R'\" doesn't solve OLL 27, it doesn't orient the last layer");
+    assert_eq!(error(&[t_perm], &[]).1, "unknown case \"T-perm\"");
+    assert_eq!(error(&[], &[r#"    {"key":[0,1,2,3,4,5,6,6],"algorithm":""}"#]).1, "unknown case [0, 1, 2, 3, 4, 5, 6, 6]");
+    assert_eq!(error(&[], &[r#"    {"algorithm":""}"#]).1, "expected either a name or a key");
+
+    // a broken file is reported when it's set, before any data is loaded with it
+    let path = std::env::temp_dir().join("rustiks-broken-overrides.json");
+    std::fs::write(&path, file(&[t_perm], &[])).unwrap();
+    let error = set_overrides(&path).unwrap_err();
+    assert_eq!((error.file, error.line), (path.display().to_string(), 4));
+    std::fs::remove_file(&path).unwrap();
+    assert!(set_overrides(&path).is_err());
+}
+
+#[test]
+fn test_generate() {
+    // only the cubes with the right parities and twists can be solved
+    for scramble in Scrambler::new(30, 7).take(20) {
+        assert!(Cube::from_algorithm(&scramble).is_solvable());
+    }
+    let mut twisted = Cube::solved();
+    twisted.rotate_corner_clockwise_illegal(pos!(0, 1, 2), 1);
+    assert!(!twisted.is_solvable());
+
+    assert_eq!(Cube::last_layer_states(false, true).len(), 216);
+    assert_eq!(Cube::last_layer_states(true, false).len(), 288);
+    assert_eq!(Cube::last_layer_states(true, true).len(), 62208);
+    // every named case shows up (with the skips)
+    assert_eq!(cases(Step::Oll), Step::Oll.keys());
+    assert_eq!(cases(Step::Pll), Step::Pll.keys());
+
+    // <R, U> is enough for the sunes in 7 moves and for the u-perms in 11
+    let oll = Generator::new(Step::Oll).with_moves(&[MoveType::R, MoveType::U]).with_max_length(8).generate();
+    let found = oll.cases.iter().filter(|(_, algorithms)| !algorithms.is_empty()).map(|(key, _)| Step::Oll.name(key)).collect::<Vec<_>>();
+    assert_eq!(found, ["OLL skip", "OLL 26", "OLL 27"]);
+    assert_eq!(oll.data()[&Step::Oll.key("OLL 27").unwrap()], Algorithm::from("R U R' U R U2 R'"));
+    assert!(oll.to_string().contains("OLL 1:\n    nothing up to 8 moves\n"));
+    // the turn of the layer before the moves isn't counted, seven are enough after it
+    let sune = Step::Oll.case(&Step::Oll.key("OLL 27").unwrap()) * Cube::from_algorithm(&auf(1));
+    let found = Generator::new(Step::Oll).with_moves(&[MoveType::R, MoveType::U]).with_max_length(7).solve(&[sune]);
+    assert_eq!(found[0][0], Algorithm::from("U' R U R' U R U2 R'"));
+
+    let pll = Generator::new(Step::Pll).with_moves(&[MoveType::R, MoveType::U]).with_max_length(11).with_alternatives(3).generate();
+    let ua = &pll.cases.iter().find(|(key, _)| Step::Pll.name(key) == "Ua-perm").unwrap().1;
+    assert_eq!(ua[0], Algorithm::from("R U' R U R U R U' R' U' R2"));
+    assert!(pll.cases.iter().all(|(_, algorithms)| algorithms.len() <= 3));
+
+    // all the alternatives work, and what's written can be loaded back
+    for generated in &[oll, pll] {
+        for (key, algorithm) in generated.cases.iter().flat_map(|(key, algorithms)| algorithms.iter().map(move |a| (key, a))) {
+            assert_eq!(last_layer_problem(generated.step, key, algorithm), None, "{:?}", algorithm);
+        }
+        let loaded = load_last_layer(generated.step, "generated.json", &generated.to_json()).unwrap();
+        assert_eq!(loaded, generated.data());
+    }
+}
+
+#[test]
+fn test_last_layer_subsets() {
+    let frame = Symmetry::rotation_to(Face::new(3), Face::new(5));
+    let d = |times: usize| (0..times).fold(Cube::solved(), |c, _| c * Cube::from_algorithm(&Algorithm::from("D")));
+    let case_of = |alg: &Algorithm| Cube::from_algorithm(&alg.reversed().symmetric(&frame));
+
+    assert_eq!([Step::Coll, Step::Zbll, Step::Wv, Step::Vls].map(|s| s.keys().len()), [43, 494, 27, 216]);
+    assert_eq!(Step::Coll.name(&Step::Coll.keys()[1]), "COLL H 1");
+    for step in &[Step::Coll, Step::Wv, Step::Vls] {
+        assert_eq!(cases(*step), step.keys());
+    }
+
+    // coll leaves the edges to pll, zbll solves everything, from any turn of the layer
+    for (i, (key, alg)) in coll_data().iter().enumerate() {
+        let mut cube = d(i / 4 % 4) * case_of(alg) * d(i % 4);
+        let case = cube.last_layer_case(Step::Coll).expect("unrecognized coll case");
+        assert_eq!(&case.key, key);
+        cube.apply(&case.algorithm);
+        let pll = cube.pll_case().expect("unrecognized pll case").name;
+        assert!(["PLL skip", "H-perm", "Ua-perm", "Ub-perm", "Z-perm"].contains(&pll), "{} leaves {}", case.name, pll);
+    }
+    for (i, (key, alg)) in zbll_data().iter().enumerate().step_by(7) {
+        let mut cube = d(i / 4 % 4) * case_of(alg) * d(i % 4);
+        let case = cube.last_layer_case(Step::Zbll).expect("unrecognized zbll case");
+        assert_eq!(&case.key, key);
+        cube.apply(&case.algorithm);
+        assert!(cube.is_solved(), "{} not solved", case.name);
+    }
+    assert!(case_of(&Algorithm::from("F R U R' U' F'")).last_layer_case(Step::Zbll).is_none());
+
+    // winter variation and vls insert the pair of any slot and orient the last layer
+    for step in &[Step::Wv, Step::Vls] {
+        for (i, (key, alg)) in step.data().iter().enumerate() {
+            let mut cube = case_of(alg).transposed(cpos!(1, 3), cpos!(FRONTS[i % 4], 3));
+            let case = cube.last_layer_case(*step).expect("unrecognized last slot case");
+            assert_eq!(&case.key, key);
+            cube.apply(&case.algorithm);
+            assert!(cube.f2l_slots().iter().all(|s| s.is_solved()), "{} breaks f2l", case.name);
+            assert_eq!(cube.oll_case().unwrap().name, "OLL skip", "{} not oriented", case.name);
+        }
+    }
+    let wv = case_of(&Algorithm::from("R U R'"));
+    assert_eq!(wv.last_layer_case(Step::Wv).unwrap().name, "WV skip");
+    assert!(case_of(&Algorithm::from("R U R' F R U R' U' F'")).last_layer_case(Step::Wv).is_none());
+    assert!(case_of(&Algorithm::from("R U R' F R U R' U' F'")).last_layer_case(Step::Vls).is_some());
+
+    // the solver uses them when it can: the algorithm of the case it's given is the
+    // solution, with pll after coll and after the last pair
+    let with = |stages: Stages, cube: Cube, step: Step| {
+        let case = cube.last_layer_case(step).unwrap_or_else(|| panic!("no {:?} case for\n{:?}", step, cube));
+        let mut after = cube;
+        after.apply(&case.algorithm);
+        let pll = after.pll_case().map_or(Algorithm::from(""), |pll| pll.algorithm);
+        assert_eq!(cube.solution_with(&stages).unwrap(), (case.algorithm + pll).simplified(), "{} isn't used", case.name);
+    };
+    for alg in zbll_data().values().step_by(25) {
+        with(Stages { zbll: true, ..Stages::default() }, case_of(alg), Step::Zbll);
+    }
+    for alg in coll_data().values().step_by(5) {
+        with(Stages { coll: true, ..Stages::default() }, case_of(alg), Step::Coll);
+    }
+    for alg in Step::Wv.data().values().step_by(3) {
+        with(Stages { wv: true, ..Stages::default() }, case_of(alg), Step::Wv);
+    }
+    for alg in Step::Vls.data().values().step_by(20) {
+        with(Stages { vls: true, ..Stages::default() }, case_of(alg), Step::Vls);
+    }
+    let stages = [
+        Stages { wv: true, coll: true, ..Stages::default() },
+        Stages { wv: true, vls: true, zbll: true, ..Stages::default() },
+    ];
+    for scramble in Scrambler::new(25, 49).take(10) {
+        let cube = Cube::from_algorithm(&scramble);
+        for stages in &stages {
+            let mut solved = cube;
+            solved.apply(&cube.solution_with(stages).unwrap());
+            assert!(solved.is_solved(), "{:?} not solved with {:?}", scramble, stages);
+        }
+    }
+}
+
+#[test]
+fn test_coverage() {
+    let frame = Symmetry::rotation_to(Face::new(3), Face::new(5));
+    // whether an algorithm (as written in the data) solves a cube up to a turn of the last layer
+    let solves = |cube: &Cube, alg: &Algorithm| (0..4).any(|t| {
+        let mut cube = *cube;
+        cube.apply(&alg.symmetric(&frame));
+        (0..t).for_each(|_| cube.apply(&Algorithm::from("D")));
+        cube.is_solved()
+    });
+
+    let coverage = coverage(&Generator::new(Step::Zbll).with_max_length(8));
+    assert_eq!(coverage.cases.len(), 3916);
+    assert_eq!(coverage.cases.iter().map(|case| case.states).sum::<usize>(), 62208);
+    assert!(coverage.missing(Step::Oll).is_empty() && coverage.missing(Step::Pll).is_empty());
+    assert!(coverage.to_string().starts_with("3916 cases (62208 states), 0 missing with two looks, nothing found for "));
+    assert!(coverage.to_string().contains(" in RUF up to 8 moves (not counting a turn of the layer first)\nover the "));
+
+    for case in coverage.cases.iter().step_by(11) {
+        assert!(solves(&case.cube, case.two_look.as_ref().unwrap()), "{} then {:?}", case.oll, case.pll);
+    }
+    for case in coverage.cases.iter().filter(|case| case.shortest_found.is_some()) {
+        assert!(solves(&case.cube, case.shortest_found.as_ref().unwrap()));
+    }
+    let skip = coverage.cases.iter().find(|case| case.cube.is_solved()).unwrap();
+    assert_eq!((skip.oll, skip.pll), ("OLL skip", Some("PLL skip")));
+    assert_eq!((skip.two_look.clone(), skip.shortest_found.clone()), (Some(Algorithm::from("")), Some(Algorithm::from(""))));
+    let costs = coverage.costliest().iter().map(|case| case.cost().unwrap()).collect::<Vec<_>>();
+    assert!(costs[0] > 0 && costs.windows(2).all(|w| w[0] >= w[1]));
+    // the averages are over the same cases, those found longer than two looks count
+    assert_eq!(costs.len(), coverage.compared().count());
+    let states = coverage.compared().map(|case| case.states).sum::<usize>() as f64;
+    let cost = coverage.compared().map(|case| case.cost().unwrap() as f64 * case.states as f64).sum::<f64>() / states;
+    let averages = coverage.average(|case| case.two_look.as_ref()) - coverage.average(|case| case.shortest_found.as_ref());
+    assert!((averages - cost).abs() < 1e-9);
+    let shorter = |coverage: &Coverage| coverage.compared().filter(|case| case.cost() < Some(0)).count();
+    let mut longer = coverage.clone();
+    let case = longer.cases.iter_mut().find(|case| case.cost() > Some(0)).unwrap();
+    case.shortest_found = Some(case.two_look.clone().unwrap() + Algorithm::from("R R'"));
+    assert_eq!(case.cost(), Some(-2));
+    assert_eq!(shorter(&longer), shorter(&coverage) + 1);
+    assert!(longer.to_string().contains(&format!(", two looks are shorter for {} cases\n", shorter(&longer))));
+
+    // an oll without an entry leaves its pll unknown
+    let mut missing = coverage.clone();
+    let sune = missing.cases.iter_mut().find(|case| case.oll == "OLL 27" && case.pll == Some("T-perm")).unwrap();
+    sune.two_look = None;
+    let t_perm = missing.cases.iter_mut().find(|case| case.oll == "OLL 1" && case.pll == Some("T-perm")).unwrap();
+    t_perm.two_look = None;
+    t_perm.pll = None;
+    assert_eq!((missing.missing(Step::Oll), missing.missing(Step::Pll)), (vec!["OLL 1"], vec!["T-perm"]));
+    assert!(missing.to_string().starts_with("missing from oll.json: OLL 1\nmissing from pll.json: T-perm\n"));
+}